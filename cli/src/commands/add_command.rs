@@ -1,13 +1,43 @@
+use crate::commands::list_command::item_row;
+use crate::commands::search_command::score_item;
 use crate::config::Config;
-use crate::schemas::registry_item::RegistryItem;
-use crate::schemas::registry_item_file::RegistryItemFile;
-use crate::schemas::registry_type::RegistryType;
+use crate::lockfile::{
+    LOCKFILE_NAME, LockedComponent, Lockfile, LockfileError, lock_key, project_root,
+};
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_index::{qualified_name, resolve_registry_indexes};
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::registry::resolve_registry_tree::{ResolvedRegistryItem, resolve_registry_tree};
+use crate::util::diff::unified_diff;
+use crate::util::get_installed_components::get_installed_components;
+use crate::util::install_packages::{
+    InstallError, PackagesToInstall, install_packages, missing_packages,
+};
+use crate::util::journal::journal_root;
+use crate::util::merge_base::merge_base_path;
+use crate::util::pick::{PickItem, pick_many};
+use crate::util::plan::{FileAction, Plan};
+use crate::util::print_table::table_lines;
+use crate::util::resolve_file_target_path::{
+    FileTargetError, ResolvedItemFile, resolve_item_files,
+};
+use crate::util::step::{LOOKING_GLASS, PAPER, SPARKLE, Step, TRUCK};
+use crate::util::transaction::{Transaction, TransactionError};
 use crate::{
-    config,
+    config, inc_step,
     preflights::add::{PreflightAdd, preflight_add},
 };
+use console::{style, user_attended};
+use dialoguer::Confirm;
+use indicatif::{MultiProgress, style::TemplateError};
+use log::info;
 use serde::{Deserialize, Serialize};
-use std::{fs, path, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -22,30 +52,100 @@ pub enum AddError {
     RegistryError(#[from] RegistryError),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
-    #[error("Content of component is empty")]
-    ContentEmpty,
+    #[error(transparent)]
+    InstallError(#[from] InstallError),
+    #[error(transparent)]
+    PromptError(#[from] dialoguer::Error),
+    #[error(transparent)]
+    LockfileError(#[from] LockfileError),
+    #[error(transparent)]
+    TemplateError(#[from] TemplateError),
     #[error("Component contains an invalid registry type")]
     InvalidRegistryType,
-    #[error(
-        "Could not resolve the path for components, check your tsconfig paths and ensure the directories exist"
-    )]
-    CouldNotResolveTargetPath,
-}
-
-#[derive(Debug, Error)]
-pub enum RegistryError {
     #[error(transparent)]
-    HttpError(#[from] reqwest::Error),
+    FileTargetError(#[from] FileTargetError),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AddSchema {
     pub cwd: PathBuf,
     pub components: Vec<String>,
+    pub no_install: bool,
+    pub overwrite: bool,
+    pub skip_existing: bool,
+    pub yes: bool,
+    pub all: bool,
+    pub concurrency: usize,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+/// A file `add` changes, with the content it leaves in it.
+#[derive(Debug)]
+struct PlannedChange {
+    path: PathBuf,
+    content: String,
+    action: FileAction,
+    /// The component the file belongs to, `None` for merge bases and the lockfile.
+    component: Option<String>,
+}
+
+impl PlannedChange {
+    fn new(path: PathBuf, content: String, action: FileAction, component: Option<&str>) -> Self {
+        Self {
+            path,
+            content,
+            action,
+            component: component.map(String::from),
+        }
+    }
+
+    fn writes(&self) -> bool {
+        matches!(
+            self.action,
+            FileAction::Create | FileAction::Overwrite | FileAction::Update
+        )
+    }
+}
+
+/// Everything `add` does, worked out once and then either printed by `--dry-run` or staged.
+#[derive(Debug)]
+struct AddPlan {
+    components: Vec<String>,
+    changes: Vec<PlannedChange>,
+    packages: PackagesToInstall,
+}
+
+impl AddPlan {
+    fn to_plan(&self, options: &AddSchema, config: &Config) -> Plan {
+        let mut plan = Plan::new("add");
+        plan.components = self.components.clone();
+
+        for change in &self.changes {
+            plan.file(
+                &change.path,
+                change.action,
+                change.component.as_deref(),
+                config,
+            );
+        }
+
+        if !options.no_install {
+            plan.install.dependencies = self.packages.dependencies.clone();
+            plan.install.dev_dependencies = self.packages.dev_dependencies.clone();
+        }
+
+        plan
+    }
 }
 
-pub async fn add_command(options: AddSchema) -> Result<(), AddError> {
-    if options.components.is_empty() {
+pub async fn add_command(mp: &MultiProgress, options: AddSchema) -> Result<(), AddError> {
+    // Picking components is interactive, so without a terminal nothing changes.
+    if options.components.is_empty() && !options.all && (options.yes || !user_attended()) {
         return Err(AddError::ComponentsEmpty);
     }
 
@@ -53,121 +153,347 @@ pub async fn add_command(options: AddSchema) -> Result<(), AddError> {
 
     let config = Config::get_config()?;
 
-    add_components(&options.components, &config, &options).await?;
+    let components = if options.all {
+        all_components(&config).await
+    } else if options.components.is_empty() {
+        pick_components(&config).await?
+    } else {
+        options.components.clone()
+    };
+
+    if components.is_empty() {
+        info!("No components to add.");
+        return Ok(());
+    }
+
+    if options.dry_run {
+        let sources = parse_sources(&components)?;
+        let registry_items =
+            resolve_registry_tree(&sources, &config, mp, options.concurrency).await?;
+
+        plan_add(&registry_items, &config, &options, None)?
+            .to_plan(&options, &config)
+            .print(options.json)?;
+        return Ok(());
+    }
+
+    let mut add_pb = Step::new(mp, 3, 3)?;
+
+    add_components(&components, &config, &options, mp, &mut add_pb)
+        .await
+        .inspect_err(|_| add_pb.abandon())?;
+
+    add_pb.finish_with(SPARKLE, "Finished adding components!");
 
     Ok(())
 }
 
+/// Every component of the configured registries, named the way `add` expects them.
+async fn all_components(config: &Config) -> Vec<String> {
+    resolve_registry_indexes(config)
+        .await
+        .iter()
+        .flat_map(|(namespace, index)| {
+            index.items.iter().map(|item| qualified_name(*namespace, &item.name))
+        })
+        .collect()
+}
+
+/// Lets the user search the registry indexes and pick components from them, grouped by type.
+/// Installed components start out checked and are left alone when they stay checked.
+async fn pick_components(config: &Config) -> Result<Vec<String>, AddError> {
+    let indexes = resolve_registry_indexes(config).await;
+    let installed = get_installed_components(config)
+        .await?
+        .into_iter()
+        .map(|component| component.name)
+        .collect::<HashSet<_>>();
+
+    let mut candidates = indexes
+        .iter()
+        .flat_map(|(namespace, index)| index.items.iter().map(move |item| (*namespace, item)))
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        info!("No components were found in the configured registries.");
+        return Ok(Vec::new());
+    }
+
+    candidates.sort_by(|(a_namespace, a), (b_namespace, b)| {
+        a.item_type
+            .cmp(&b.item_type)
+            .then_with(|| a_namespace.cmp(b_namespace))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let rows = candidates
+        .iter()
+        .map(|(namespace, item)| item_row(*namespace, item))
+        .collect::<Vec<_>>();
+    let labels = rows
+        .iter()
+        .map(|[name, _, title, description]| [name.clone(), title.clone(), description.clone()])
+        .collect::<Vec<_>>();
+    let (_, lines) = table_lines(["Name", "Title", "Description"], &labels);
+
+    let items = rows
+        .iter()
+        .zip(lines)
+        .map(|([name, item_type, ..], line)| PickItem {
+            group: item_type.clone(),
+            label: if installed.contains(name) {
+                format!("{line} {}", style("(installed)").dim())
+            } else {
+                line
+            },
+            checked: installed.contains(name),
+        })
+        .collect::<Vec<_>>();
+
+    let selected = pick_many("Select the components to add", &items, |index, terms| {
+        score_item(terms, candidates[index].1).is_some()
+    })?;
+
+    let selected = selected.into_iter().map(|index| &rows[index][0]).collect::<HashSet<_>>();
+    let (kept, added): (Vec<_>, Vec<_>) =
+        rows.iter().map(|[name, ..]| name).partition(|name| installed.contains(*name));
+
+    if kept.iter().any(|name| !selected.contains(name)) {
+        info!("Unchecking an installed component keeps it, use `lumina remove` to remove it.");
+    }
+
+    Ok(added.into_iter().filter(|name| selected.contains(name)).cloned().collect())
+}
+
 async fn add_components(
-    components: &Vec<String>,
+    components: &[String],
     config: &Config,
     options: &AddSchema,
+    mp: &MultiProgress,
+    add_pb: &mut Step,
 ) -> Result<(), AddError> {
-    for component in components {
-        let registry_item = resolve_registry_item(component).await?;
+    add_pb.step_before(LOOKING_GLASS, "Resolving components...");
+    let registry_items =
+        resolve_registry_tree(&parse_sources(components)?, config, mp, options.concurrency).await?;
+    add_pb.step_after();
 
-        // TODO: We need to check if the component has any registry dependents, if so we need to add them as well
+    // Writing may prompt for confirmation, so the spinner stays still while it runs.
+    inc_step!(add_pb, PAPER, "Writing component files...");
 
-        // Next we need to loop over the files in the registry item and add them accordingly
+    let plan = plan_add(&registry_items, config, options, Some(mp))?;
 
-        if !registry_item.files.is_empty() {
-            for file in registry_item.files {
-                let target_dir = resolve_file_target_path(&file, &config);
+    let mut tx = Transaction::begin(&journal_root(&options.cwd)?);
 
-                if target_dir.is_none() {
-                    return Err(AddError::CouldNotResolveTargetPath);
-                }
+    for change in plan.changes.iter().filter(|change| change.writes()) {
+        tx.write(&change.path, &change.content)?;
+    }
 
-                let target_dir = target_dir.unwrap();
+    tx.commit()?;
 
-                let component_path = target_dir.join(&registry_item.name);
-                fs::create_dir_all(&component_path)?;
+    print_summary(&plan, config);
 
-                let (name, ext) = match file.item_type {
-                    RegistryType::Component => ("index", "tsx"),
-                    RegistryType::UI => ("index", "tsx"),
-                    RegistryType::Style => (registry_item.name.as_str(), "rsml"),
-                    _ => ("index", "tsx"),
-                };
+    add_pb.inc();
+    add_pb.step_before_no_tick(TRUCK, "Installing dependencies...");
 
-                fs::write(
-                    &component_path.join(format!("{}.{}", name, ext)),
-                    &file.content.clone().ok_or(AddError::ContentEmpty)?,
-                )?;
-            }
+    let packages = &plan.packages;
+
+    if options.no_install {
+        if !packages.is_empty() {
+            info!(
+                "Skipping installation of {}",
+                packages
+                    .dependencies
+                    .iter()
+                    .chain(&packages.dev_dependencies)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
         }
+    } else if !packages.is_empty() {
+        install_packages(&options.cwd, packages)?;
+    }
+
+    add_pb.step_after();
 
-        // if !registry_item.files.is_empty() {
-        //     for file in registry_item.files {
-        //         // We also need to check the type of the file, if it's a component we add it into whatever they specified for their components path, if it's a ui we add it to the ui path
-        //
-        //         let project_info =
-        //             get_project_info(&options.cwd).expect("Couldn't get project info");
-        //
-        //         let project_paths = match file.item_type {
-        //             RegistryType::Component => project_info
-        //                 .aliases_paths
-        //                 .get(aliases.components.as_ref().unwrap().as_str()),
-        //             RegistryType::UI => {
-        //                 project_info.aliases_paths.get(aliases.ui.as_ref().unwrap().as_str())
-        //             }
-        //             _ => None,
-        //         };
-        //
-        //         let paths = project_paths
-        //             .map(|paths| {
-        //                 paths
-        //                     .iter()
-        //                     .map(|p| {
-        //                         let s = p.to_string_lossy();
-        //                         PathBuf::from(s.trim_end_matches("/*"))
-        //                     })
-        //                     .collect::<Vec<PathBuf>>()
-        //             })
-        //             .unwrap_or_default();
-        //
-        //         for path in paths {
-        //             let component_path = path.join(&registry_item.name);
-        //             fs::create_dir_all(&component_path)?;
-        //             fs::write(
-        //                 &component_path.join(format!("{}.tsx", &registry_item.name)),
-        //                 &file.content.clone().ok_or(AddError::ContentEmpty)?,
-        //             )?;
-        //
-        //             if file.item_type == RegistryType::Style {
-        //                 fs::write(
-        //                     &component_path.join(format!("{}.rsml", &registry_item.name)),
-        //                     &file.content.clone().ok_or(AddError::ContentEmpty)?,
-        //                 )?;
-        //             }
-        //         }
-        //     }
-        // }
-    }
-
-    // Check registry for component
-    // Check if file already exists and whether to overwrite it or not, get confirmation
-    // If all checks pass write to file at directory specified.
     Ok(())
 }
 
-// TODO: Move these functions to their own designated modules
-async fn resolve_registry_item(component: &String) -> Result<RegistryItem, RegistryError> {
-    // fetch the registry for the given component
-    // TODO: We need to handle the errors better to be able to better report to the user, as right now it will just be a generic error from reqwest.
-    let result = reqwest::get(format!("https://lumina-ui.com/r/{component}.json"))
-        .await?
-        .json::<RegistryItem>()
-        .await?;
+/// Works out every file `add` changes and what it changes it to. With a progress bar to suspend
+/// the user is asked about overwriting local changes, otherwise those files stay a prompt.
+fn plan_add(
+    registry_items: &[ResolvedRegistryItem],
+    config: &Config,
+    options: &AddSchema,
+    mp: Option<&MultiProgress>,
+) -> Result<AddPlan, AddError> {
+    let root = project_root(config);
+    let mut lockfile = Lockfile::read(&root)?.unwrap_or_default();
+
+    let mut components = Vec::new();
+    let mut changes = Vec::new();
+    let mut dependencies = Vec::new();
+    let mut dev_dependencies = Vec::new();
+
+    for resolved in registry_items {
+        let registry_item = &resolved.item;
+        let key = lock_key(resolved);
+
+        dependencies.extend(registry_item.dependencies.iter().flatten().cloned());
+        dev_dependencies.extend(registry_item.dev_dependencies.iter().flatten().cloned());
+
+        // Files the user kept their own version of aren't recorded, they don't hold the registry's
+        // content and `update` must not treat them as unmodified.
+        let mut written = Vec::new();
+
+        for file in resolve_item_files(registry_item, config)? {
+            let mut action = planned_write(&file.path, &file.content, options)?;
 
-    Ok(result)
+            if let (FileAction::Prompt, Some(mp)) = (action, mp) {
+                action = match mp.suspend(|| confirm_overwrite(&file))? {
+                    true => FileAction::Overwrite,
+                    false => FileAction::Skip,
+                };
+            }
+
+            changes.push(PlannedChange::new(
+                file.path.clone(),
+                file.content.clone(),
+                action,
+                Some(&key),
+            ));
+
+            if action != FileAction::Skip {
+                written.push(file);
+            }
+        }
+
+        // The registry content is only the common ancestor of files that contain it.
+        for file in &written {
+            let Some(base) = merge_base_path(&file.path, config) else {
+                continue;
+            };
+            let action = planned_update(&base, &file.content)?;
+
+            changes.push(PlannedChange::new(base, file.content.clone(), action, None));
+        }
+
+        lockfile.components.insert(
+            key.clone(),
+            LockedComponent::new(resolved, &written, config)?,
+        );
+        components.push(key);
+    }
+
+    let lockfile_path = root.join(LOCKFILE_NAME);
+    let content = lockfile.to_json()?;
+    let action = planned_update(&lockfile_path, &content)?;
+    changes.push(PlannedChange::new(lockfile_path, content, action, None));
+
+    Ok(AddPlan {
+        components,
+        changes,
+        packages: missing_packages(&options.cwd, &dependencies, &dev_dependencies),
+    })
 }
 
-fn resolve_file_target_path(file: &RegistryItemFile, config: &Config) -> Option<PathBuf> {
-    return match file.item_type {
-        RegistryType::Component => config.resolved_paths.components.clone(),
-        RegistryType::Block => config.resolved_paths.components.clone(),
-        RegistryType::UI => config.resolved_paths.ui.clone(),
-        _ => config.resolved_paths.components.clone(),
+fn parse_sources(components: &[String]) -> Result<Vec<RegistrySource>, AddError> {
+    Ok(components
+        .iter()
+        .map(|component| RegistrySource::parse(component))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+fn planned_write(path: &Path, content: &str, options: &AddSchema) -> Result<FileAction, AddError> {
+    let existing = match fs::read_to_string(path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(FileAction::Create),
+        Err(e) => return Err(e.into()),
     };
+
+    Ok(if existing == content {
+        FileAction::Unchanged
+    } else {
+        match overwrite_decision(options) {
+            Some(true) => FileAction::Overwrite,
+            Some(false) => FileAction::Skip,
+            None => FileAction::Prompt,
+        }
+    })
+}
+
+/// What writing `content` does to a file lumina manages itself, where nothing needs confirming.
+fn planned_update(path: &Path, content: &str) -> Result<FileAction, AddError> {
+    match fs::read_to_string(path) {
+        Ok(existing) if existing == content => Ok(FileAction::Unchanged),
+        Ok(_) => Ok(FileAction::Update),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(FileAction::Create),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the flags decide if changed files get overwritten, `None` when the user is asked.
+fn overwrite_decision(options: &AddSchema) -> Option<bool> {
+    if options.overwrite {
+        return Some(true);
+    }
+
+    // Without a terminal to prompt on we keep local files, same as answering the prompt's default.
+    if options.skip_existing || options.yes || !user_attended() {
+        return Some(false);
+    }
+
+    None
+}
+
+fn confirm_overwrite(file: &ResolvedItemFile) -> Result<bool, AddError> {
+    let existing = fs::read_to_string(&file.path)?;
+
+    if let Some(diff) = unified_diff(&existing, &file.content, "local", "registry") {
+        println!("{diff}");
+    }
+
+    let confirmation = Confirm::new()
+        .with_prompt(format!(
+            "{} already exists. Overwrite it with the changes above?",
+            style(file.path.display()).bold().cyan()
+        ))
+        .default(false)
+        .interact()?;
+
+    Ok(confirmation)
+}
+
+fn print_summary(plan: &AddPlan, config: &Config) {
+    let sections = [
+        ("Created", FileAction::Create),
+        ("Overwritten", FileAction::Overwrite),
+        ("Skipped", FileAction::Skip),
+        ("Unchanged", FileAction::Unchanged),
+    ];
+
+    for (label, action) in sections {
+        let paths = plan
+            .changes
+            .iter()
+            .filter(|change| change.component.is_some() && change.action == action)
+            .map(|change| &change.path)
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            continue;
+        }
+
+        info!("{} {} file(s):", style(label).bold(), paths.len());
+
+        for path in paths {
+            info!(
+                "  - {}",
+                style(config.resolved_paths.relative(path).display()).dim()
+            );
+        }
+    }
 }
@@ -1,9 +1,12 @@
 use crate::preflights::build::{PreflightError, preflight_build};
+use crate::registry::resolve_registry_version::VERSIONS_INDEX;
 use crate::schemas::registry::Registry;
+use crate::schemas::registry_item_versions::RegistryItemVersions;
 use log::info;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -14,6 +17,8 @@ pub enum BuildError {
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
+    #[error("`{0}` has an invalid version `{1}`: {2}")]
+    InvalidVersion(String, String, #[source] semver::Error),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -36,22 +41,74 @@ pub fn build_command(options: BuildSchema) -> Result<(), BuildError> {
         ));
 
         for file in &mut item.files {
-            let content = fs::read_to_string(&resolved_paths.cwd.join(&file.path))?;
+            let content = fs::read_to_string(resolved_paths.cwd.join(&file.path))?;
             file.content = Some(content);
         }
 
-        fs::write(
-            &resolved_paths.output.join(format!("{}.json", item.name)),
-            &serde_json::to_string_pretty(&item)?,
-        )?;
+        let data = serde_json::to_string_pretty(&item)?;
+
+        let is_latest = match &item.version {
+            Some(version) => write_version(&resolved_paths.output, &item.name, version, &data)?,
+            None => true,
+        };
+
+        // `<name>.json` always holds the latest version, for clients that don't ask for one.
+        if is_latest {
+            fs::write(
+                resolved_paths.output.join(format!("{}.json", item.name)),
+                &data,
+            )?;
+        }
     }
 
     fs::copy(
         &resolved_paths.registry,
-        &resolved_paths.output.join("registry.json"),
+        resolved_paths.output.join("registry.json"),
     )?;
 
     info!("Build successful....");
 
     Ok(())
 }
+
+/// Writes `<name>/<version>.json` and the `<name>/versions.json` index pointing at the latest
+/// version, returning whether `version` is the latest.
+fn write_version(output: &Path, name: &str, version: &str, data: &str) -> Result<bool, BuildError> {
+    let version = Version::parse(version)
+        .map_err(|e| BuildError::InvalidVersion(name.to_string(), version.to_string(), e))?;
+
+    let directory = output.join(name);
+    fs::create_dir_all(&directory)?;
+    fs::write(directory.join(format!("{version}.json")), data)?;
+
+    // Versions from earlier builds stay published, so the index is rebuilt from the directory.
+    let mut versions = Vec::new();
+
+    for entry in fs::read_dir(&directory)? {
+        let path = entry?.path();
+
+        if path.extension().is_some_and(|extension| extension == "json")
+            && let Some(parsed) =
+                path.file_stem().and_then(|stem| Version::parse(&stem.to_string_lossy()).ok())
+        {
+            versions.push(parsed);
+        }
+    }
+
+    versions.sort();
+
+    let latest = versions.last().unwrap_or(&version).clone();
+
+    let index = RegistryItemVersions {
+        name: name.to_string(),
+        latest: latest.to_string(),
+        versions: versions.iter().map(Version::to_string).collect(),
+    };
+
+    fs::write(
+        directory.join(format!("{VERSIONS_INDEX}.json")),
+        serde_json::to_string_pretty(&index)?,
+    )?;
+
+    Ok(latest == version)
+}
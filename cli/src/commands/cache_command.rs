@@ -0,0 +1,147 @@
+use crate::registry::registry_cache::{CacheEntry, cache_entries, registry_cache_dir, unix_now};
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::util::print_table::print_table;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind};
+use thiserror::Error;
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum CacheAction {
+    List,
+    Clean,
+    Prune { max_age_days: u64 },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheSchema {
+    pub action: CacheAction,
+}
+
+pub fn cache_command(options: CacheSchema) -> Result<(), CacheError> {
+    match options.action {
+        CacheAction::List => list_cache(),
+        CacheAction::Clean => clean_cache(),
+        CacheAction::Prune { max_age_days } => prune_cache(max_age_days),
+    }
+}
+
+fn list_cache() -> Result<(), CacheError> {
+    let entries = cache_entries()?;
+
+    if entries.is_empty() {
+        info!(
+            "The registry cache at {} is empty.",
+            registry_cache_dir()?.display()
+        );
+        return Ok(());
+    }
+
+    let now = unix_now();
+    let header = ["Url", "Size", "Age", "Validator"];
+
+    let cells = entries
+        .iter()
+        .map(|entry| match &entry.response {
+            Some(response) => [
+                response.url.clone(),
+                format_size(entry.size),
+                format_age(now.saturating_sub(response.fetched_at)),
+                String::from(match (&response.etag, &response.last_modified) {
+                    (Some(_), _) => "etag",
+                    (None, Some(_)) => "last-modified",
+                    (None, None) => "none",
+                }),
+            ],
+            None => [
+                entry.path.display().to_string(),
+                format_size(entry.size),
+                String::from("-"),
+                String::from("unreadable"),
+            ],
+        })
+        .collect::<Vec<_>>();
+
+    print_table(header, &cells);
+
+    info!(
+        "{} cached response(s), {} in {}",
+        entries.len(),
+        format_size(entries.iter().map(|entry| entry.size).sum()),
+        registry_cache_dir()?.display()
+    );
+
+    Ok(())
+}
+
+fn clean_cache() -> Result<(), CacheError> {
+    let entries = cache_entries()?;
+
+    match fs::remove_dir_all(registry_cache_dir()?) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    info!(
+        "Removed {} cached response(s), freeing {}.",
+        entries.len(),
+        format_size(entries.iter().map(|entry| entry.size).sum())
+    );
+
+    Ok(())
+}
+
+/// Removes responses the registry hasn't confirmed within `max_age_days`, along with anything
+/// that can't be read back.
+fn prune_cache(max_age_days: u64) -> Result<(), CacheError> {
+    let cutoff = unix_now().saturating_sub(max_age_days.saturating_mul(SECONDS_PER_DAY));
+
+    let stale = cache_entries()?
+        .into_iter()
+        .filter(|entry| is_stale(entry, cutoff))
+        .collect::<Vec<_>>();
+
+    for entry in &stale {
+        fs::remove_file(&entry.path)?;
+    }
+
+    info!(
+        "Pruned {} cached response(s) older than {max_age_days} day(s), freeing {}.",
+        stale.len(),
+        format_size(stale.iter().map(|entry| entry.size).sum())
+    );
+
+    Ok(())
+}
+
+fn is_stale(entry: &CacheEntry, cutoff: u64) -> bool {
+    entry.response.as_ref().is_none_or(|response| response.fetched_at < cutoff)
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..SECONDS_PER_DAY => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / SECONDS_PER_DAY),
+    }
+}
@@ -0,0 +1,120 @@
+use crate::config::{Config, ConfigError};
+use crate::lockfile::LockfileError;
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_item::{RegistryError, resolve_registry_item};
+use crate::util::diff::unified_diff;
+use crate::util::get_installed_components::get_installed_components;
+use crate::util::resolve_file_target_path::{
+    FileTargetError, ResolvedItemFile, resolve_item_files,
+};
+use console::style;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind, path::PathBuf};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    FileTargetError(#[from] FileTargetError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    LockfileError(#[from] LockfileError),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffSchema {
+    pub cwd: PathBuf,
+    pub component: Option<String>,
+}
+
+pub async fn diff_command(options: DiffSchema) -> Result<(), DiffError> {
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+
+    match &options.component {
+        Some(component) => diff_component(component, &config).await,
+        None => list_changed_components(&config).await,
+    }
+}
+
+async fn diff_component(component: &str, config: &Config) -> Result<(), DiffError> {
+    for source in RegistrySource::parse(component)? {
+        let item = resolve_registry_item(&source, config).await?;
+        let mut changed = false;
+
+        for file in resolve_item_files(&item, config)? {
+            let local = read_local_file(&file)?;
+            let display = config.resolved_paths.relative(&file.path).display();
+
+            if let Some(diff) = unified_diff(
+                local.as_deref().unwrap_or_default(),
+                &file.content,
+                &format!("{display} (local)"),
+                &format!("{display} (registry)"),
+            ) {
+                changed = true;
+                println!("{diff}");
+            }
+        }
+
+        if !changed {
+            info!("{} is up to date.", style(&item.name).bold().cyan());
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_changed_components(config: &Config) -> Result<(), DiffError> {
+    let mut changed = Vec::new();
+
+    for component in get_installed_components(config).await? {
+        for source in RegistrySource::parse(&component.source)? {
+            let item = resolve_registry_item(&source, config).await?;
+
+            for file in resolve_item_files(&item, config)? {
+                if read_local_file(&file)?.is_none_or(|local| local != file.content) {
+                    changed.push(component.name.clone());
+                    break;
+                }
+            }
+        }
+    }
+
+    if changed.is_empty() {
+        info!("No installed components have upstream changes.");
+        return Ok(());
+    }
+
+    info!("The following components have upstream changes:");
+
+    for name in &changed {
+        info!("  - {}", style(name).bold().cyan());
+    }
+
+    info!(
+        "Run {} to see the changes.",
+        style("lumina diff <component>").bold()
+    );
+
+    Ok(())
+}
+
+fn read_local_file(file: &ResolvedItemFile) -> Result<Option<String>, DiffError> {
+    match fs::read_to_string(&file.path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::commands::cache_command::format_age;
+use crate::registry::registry_cache::unix_now;
+use crate::util::journal::{JournalEntry, journal_root, read_journal, undone_entries};
+use crate::util::print_table::print_table;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistorySchema {
+    pub cwd: PathBuf,
+}
+
+pub fn history_command(options: HistorySchema) -> Result<(), HistoryError> {
+    let entries = read_journal(&journal_root(&options.cwd)?)?;
+
+    if entries.is_empty() {
+        info!("No operations have been recorded in this project yet.");
+        return Ok(());
+    }
+
+    let now = unix_now();
+    let undone = undone_entries(&entries);
+
+    let rows = entries
+        .iter()
+        .rev()
+        .map(|entry| {
+            [
+                entry.id.to_string(),
+                format!("{} ago", format_age(now.saturating_sub(entry.timestamp))),
+                format!("lumina {}", entry.command),
+                entry.files.len().to_string(),
+                status(entry, &undone),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print_table(["Id", "When", "Command", "Files", "Status"], &rows);
+
+    info!("Run `lumina undo` to revert the latest operation, or `lumina undo <id>` for another.");
+
+    Ok(())
+}
+
+fn status(entry: &JournalEntry, undone: &HashSet<u64>) -> String {
+    match entry.undoes {
+        _ if undone.contains(&entry.id) => String::from("undone"),
+        Some(id) => format!("undid #{id}"),
+        None => String::new(),
+    }
+}
@@ -1,5 +1,7 @@
 use crate::preflights::init::ERRORS;
 use crate::util::get_project_info::{TsAliasError, write_paths};
+use crate::util::journal::journal_root;
+use crate::util::transaction::{Transaction, TransactionError};
 use crate::{
     NPM,
     config::{Config, ConfigError},
@@ -15,8 +17,6 @@ use serde::{Deserialize, Serialize};
 use std::{
     env::current_dir,
     fs,
-    fs::File,
-    io::BufWriter,
     path::PathBuf,
     process::{Command, Stdio},
 };
@@ -46,6 +46,9 @@ pub enum InitError {
 
     #[error(transparent)]
     TsAliasError(#[from] TsAliasError),
+
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
 }
 
 static PACKAGES: [&str; 1] = ["@rbxts/react"];
@@ -60,6 +63,8 @@ pub struct InitSchema {
 
 pub fn init_command(mp: &MultiProgress, options: InitSchema) -> Result<(), InitError> {
     let mut init_pb = Step::new(mp, 6, 6)?;
+    // Both config files are only moved into place once every step succeeded.
+    let mut tx = Transaction::begin(&journal_root(&options.cwd)?);
 
     if !options.skip_preflight {
         step!(init_pb, TRUCK, "Starting preflight checks.");
@@ -102,14 +107,14 @@ pub fn init_command(mp: &MultiProgress, options: InitSchema) -> Result<(), InitE
                 init_pb,
                 PAPER,
                 "Writing components.json",
-                generate_components_json(&options)?
+                generate_components_json(&mut tx, &options)?
             );
 
             inc_step!(
                 init_pb,
                 PAPER,
                 "Writing paths to tsconfig.json",
-                write_paths(&options.cwd)?
+                write_paths(&mut tx, &options.cwd)?
             );
         }
     }
@@ -127,6 +132,8 @@ pub fn init_command(mp: &MultiProgress, options: InitSchema) -> Result<(), InitE
         check_for_required_deps(&mut init_pb)?
     );
 
+    tx.commit()?;
+
     init_pb.inc();
     init_pb.finish_with(SPARKLE, "Finished initializing lumina!");
 
@@ -139,13 +146,14 @@ fn create_default_directories(options: &InitSchema) -> Result<(), InitError> {
     Ok(())
 }
 
-fn generate_components_json(options: &InitSchema) -> Result<(), InitError> {
+fn generate_components_json(tx: &mut Transaction, options: &InitSchema) -> Result<(), InitError> {
     // TODO: Merge backup config if it exists and force is not being used
     let _backup_path = current_dir()?.join("components.json.bak");
 
-    let file = File::create(options.cwd.join("components.json"))?;
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, &Config::default())?;
+    tx.write(
+        &options.cwd.join("components.json"),
+        serde_json::to_string_pretty(&Config::default())?,
+    )?;
 
     Ok(())
 }
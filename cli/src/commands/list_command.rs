@@ -0,0 +1,57 @@
+use crate::config::{Config, ConfigError};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::resolve_registry_index::{qualified_name, resolve_registry_indexes};
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::print_table::print_table;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ListError {
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListSchema {
+    pub cwd: PathBuf,
+}
+
+pub const ITEM_HEADER: [&str; 4] = ["Name", "Type", "Title", "Description"];
+
+pub async fn list_command(options: ListSchema) -> Result<(), ListError> {
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+
+    let rows = resolve_registry_indexes(&config)
+        .await
+        .iter()
+        .flat_map(|(namespace, index)| index.items.iter().map(|item| item_row(*namespace, item)))
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        info!("No components were found in the configured registries.");
+        return Ok(());
+    }
+
+    print_table(ITEM_HEADER, &rows);
+
+    Ok(())
+}
+
+/// The cells `list` and `search` show for an item, named the way it is passed to `lumina add`.
+pub fn item_row(namespace: Option<&str>, item: &RegistryItem) -> [String; 4] {
+    let item_type = item.item_type.to_string();
+
+    [
+        qualified_name(namespace, &item.name),
+        item_type.trim_start_matches("registry:").to_string(),
+        item.title.clone(),
+        item.description.clone(),
+    ]
+}
@@ -1,3 +1,13 @@
 pub mod add_command;
 pub mod build_command;
+pub mod cache_command;
+pub mod diff_command;
+pub mod history_command;
 pub mod init_command;
+pub mod list_command;
+pub mod outdated_command;
+pub mod remove_command;
+pub mod search_command;
+pub mod undo_command;
+pub mod update_command;
+pub mod view_command;
@@ -0,0 +1,168 @@
+use crate::config::{Config, ConfigError};
+use crate::lockfile::{
+    LOCKFILE_NAME, LockedComponent, Lockfile, LockfileError, hash_content, lock_path, project_root,
+};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_item::{RegistryError, resolve_registry_item};
+use crate::util::print_table::table_lines;
+use crate::util::resolve_file_target_path::{FileTargetError, resolve_item_files};
+use console::style;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OutdatedError {
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    FileTargetError(#[from] FileTargetError),
+    #[error(transparent)]
+    LockfileError(#[from] LockfileError),
+    #[error("No {LOCKFILE_NAME} was found, add components before checking for updates")]
+    NoLockfile,
+    #[error("{0} component(s) are outdated")]
+    ComponentsOutdated(usize),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutdatedSchema {
+    pub cwd: PathBuf,
+}
+
+struct OutdatedRow {
+    name: String,
+    installed_version: String,
+    installed_hash: String,
+    latest_version: String,
+    latest_hash: String,
+    local_edits: bool,
+    outdated: bool,
+}
+
+pub async fn outdated_command(options: OutdatedSchema) -> Result<(), OutdatedError> {
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+    let lockfile = Lockfile::read(&project_root(&config))?.ok_or(OutdatedError::NoLockfile)?;
+
+    let mut rows = Vec::new();
+
+    for (name, locked) in &lockfile.components {
+        rows.push(check_component(name, locked, &config).await?);
+    }
+
+    print_table(&rows);
+
+    let outdated = rows.iter().filter(|row| row.outdated).count();
+
+    if outdated > 0 {
+        return Err(OutdatedError::ComponentsOutdated(outdated));
+    }
+
+    info!("All components are up to date.");
+
+    Ok(())
+}
+
+async fn check_component(
+    name: &str,
+    locked: &LockedComponent,
+    config: &Config,
+) -> Result<OutdatedRow, OutdatedError> {
+    let mut latest_files = BTreeMap::new();
+    let mut latest_version = None;
+
+    for source in RegistrySource::parse(&locked.source)? {
+        // Pinned components are compared against the latest version, not the one they pin.
+        let item = resolve_registry_item(&source.unversioned(), config).await?;
+        latest_version = item.version.clone();
+
+        for file in resolve_item_files(&item, config)? {
+            latest_files.insert(
+                lock_path(config.resolved_paths.relative(&file.path)),
+                hash_content(&file.content),
+            );
+        }
+    }
+
+    let root = project_root(config);
+    let on_disk = |path: &str| fs::read(root.join(path)).ok().map(hash_content);
+
+    // Files upstream lists that aren't locked were kept by the user when they were added, or are
+    // new upstream when they aren't in the project either.
+    let (kept, added): (Vec<_>, Vec<_>) = latest_files
+        .keys()
+        .filter(|path| !locked.files.contains_key(*path))
+        .cloned()
+        .partition(|path| on_disk(path).is_some());
+
+    let changed = locked
+        .files
+        .iter()
+        .any(|(path, hash)| latest_files.get(path).is_none_or(|latest| latest != hash));
+    let local_edits = !kept.is_empty()
+        || locked.files.iter().any(|(path, hash)| on_disk(path).as_ref() != Some(hash));
+
+    latest_files.retain(|path, _| !kept.contains(path));
+
+    Ok(OutdatedRow {
+        name: name.to_string(),
+        outdated: changed || !added.is_empty() || latest_version != locked.version,
+        installed_version: locked.version.clone().unwrap_or_else(|| String::from("-")),
+        installed_hash: combined_hash(&locked.files),
+        latest_version: latest_version.unwrap_or_else(|| String::from("-")),
+        latest_hash: combined_hash(&latest_files),
+        local_edits,
+    })
+}
+
+/// A short hash over every file of a component, for display.
+fn combined_hash(files: &BTreeMap<String, String>) -> String {
+    let joined = files.iter().map(|(path, hash)| format!("{path}:{hash}\n")).collect::<String>();
+
+    hash_content(&joined)[..8].to_string()
+}
+
+fn print_table(rows: &[OutdatedRow]) {
+    let header = [
+        "Component",
+        "Installed",
+        "Hash",
+        "Latest",
+        "Hash",
+        "Local edits",
+    ];
+
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                row.name.clone(),
+                row.installed_version.clone(),
+                row.installed_hash.clone(),
+                row.latest_version.clone(),
+                row.latest_hash.clone(),
+                String::from(if row.local_edits { "yes" } else { "no" }),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let (header, lines) = table_lines(header, &cells);
+
+    println!("{}", style(header).bold().underlined());
+
+    for (row, line) in rows.iter().zip(lines) {
+        if row.outdated {
+            println!("{}", style(line).yellow());
+        } else {
+            println!("{line}");
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use crate::config::{Config, ConfigError};
+use crate::lockfile::{LOCKFILE_NAME, LockedComponent, Lockfile, LockfileError, project_root};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::registry_source::{RegistrySource, split_version};
+use crate::registry::resolve_registry_item::{
+    RegistryError, parse_namespace, resolve_registry_item,
+};
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::get_installed_components::{InstalledComponent, get_installed_components};
+use crate::util::install_packages::{
+    InstallError, declared_packages, package_name, uninstall_packages,
+};
+use crate::util::journal::journal_root;
+use crate::util::merge_base::{prune_merge_base, remove_merge_base};
+use crate::util::plan::{FileAction, Plan};
+use crate::util::resolve_file_target_path::{FileTargetError, resolve_item_file_path};
+use crate::util::transaction::{Transaction, TransactionError};
+use console::{style, user_attended};
+use dialoguer::Confirm;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RemoveError {
+    #[error("Passed in components were empty")]
+    ComponentsEmpty,
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    FileTargetError(#[from] FileTargetError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    InstallError(#[from] InstallError),
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
+    #[error(transparent)]
+    PromptError(#[from] dialoguer::Error),
+    #[error(transparent)]
+    LockfileError(#[from] LockfileError),
+    #[error("`{0}` is not installed")]
+    NotInstalled(String),
+    #[error("`{0}` is still required by {1}, pass --force to remove it anyway")]
+    RequiredBy(String, String),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoveSchema {
+    pub cwd: PathBuf,
+    pub components: Vec<String>,
+    pub force: bool,
+    pub yes: bool,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+struct RemovedComponent {
+    name: String,
+    /// The name of the registry item, which other components may depend on instead of `name`.
+    item_name: String,
+    files: Vec<PathBuf>,
+    /// The npm packages the component declares.
+    packages: Vec<String>,
+}
+
+impl RemovedComponent {
+    /// Takes the files from the lock entry, which are what `add` actually wrote.
+    fn locked(name: &str, locked: &LockedComponent, config: &Config) -> Self {
+        Self {
+            item_name: parse_namespace(name).map_or(name, |(_, item)| item).to_string(),
+            name: name.to_string(),
+            files: locked.file_paths(config),
+            packages: locked.dependencies.iter().chain(&locked.dev_dependencies).cloned().collect(),
+        }
+    }
+
+    /// Takes the files the registry lists for the item now, for projects without a lockfile.
+    fn fetched(name: String, item: &RegistryItem, config: &Config) -> Result<Self, RemoveError> {
+        Ok(Self {
+            name,
+            item_name: item.name.clone(),
+            files: item
+                .files
+                .iter()
+                .map(|file| resolve_item_file_path(item, file, config))
+                .collect::<Result<_, _>>()?,
+            packages: item
+                .dependencies
+                .iter()
+                .chain(&item.dev_dependencies)
+                .flatten()
+                .cloned()
+                .collect(),
+        })
+    }
+
+    fn is_required_by(&self, component: &InstalledComponent) -> bool {
+        component.depends_on(&self.name) || component.depends_on(&self.item_name)
+    }
+}
+
+pub async fn remove_command(options: RemoveSchema) -> Result<(), RemoveError> {
+    if options.components.is_empty() {
+        return Err(RemoveError::ComponentsEmpty);
+    }
+
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+    let installed = get_installed_components(&config).await?;
+
+    let root = project_root(&config);
+    let lockfile = Lockfile::read(&root)?;
+    let mut removed = Vec::new();
+
+    for component in &options.components {
+        match &lockfile {
+            Some(lockfile) => {
+                let (name, locked) = lockfile
+                    .components
+                    .iter()
+                    .find(|(name, locked)| {
+                        *name == split_version(component).0 || locked.source == *component
+                    })
+                    .ok_or_else(|| RemoveError::NotInstalled(component.clone()))?;
+
+                removed.push(RemovedComponent::locked(name, locked, &config));
+            }
+            None => {
+                for source in RegistrySource::parse(component)? {
+                    let item = resolve_registry_item(&source, &config).await?;
+                    removed.push(RemovedComponent::fetched(
+                        source.unversioned().to_string(),
+                        &item,
+                        &config,
+                    )?);
+                }
+            }
+        }
+    }
+
+    let remaining = installed
+        .iter()
+        .filter(|component| {
+            !removed.iter().any(|removed| {
+                removed.name == component.name || removed.item_name == component.name
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if options.dry_run {
+        plan_remove(&removed, &remaining, &options, &config)?.print(options.json)?;
+        return Ok(());
+    }
+
+    if !confirm_dependents(&removed, &remaining, &options)? {
+        error!("Aborting removal.");
+        return Ok(());
+    }
+
+    let mut tx = Transaction::begin(&journal_root(&options.cwd)?);
+    let mut deleted = Vec::new();
+
+    for path in removed.iter().flat_map(|component| &component.files) {
+        if !path.is_file() {
+            continue;
+        }
+
+        tx.remove(path);
+        remove_merge_base(&mut tx, path, &config);
+        deleted.push(path.clone());
+    }
+
+    if let Some(mut lockfile) = lockfile {
+        for component in &removed {
+            if lockfile.components.remove(&component.name).is_none() {
+                lockfile.components.remove(&component.item_name);
+            }
+        }
+
+        lockfile.write(&mut tx, &root)?;
+    }
+
+    tx.commit()?;
+
+    for path in &deleted {
+        prune_merge_base(path, &config);
+        remove_empty_parents(path, &config);
+    }
+
+    if deleted.is_empty() {
+        info!("No installed files were found for the given component(s).");
+    } else {
+        info!("{} {} file(s):", style("Deleted").bold(), deleted.len());
+
+        for path in &deleted {
+            info!(
+                "  - {}",
+                style(config.resolved_paths.relative(path).display()).dim()
+            );
+        }
+    }
+
+    uninstall_unused_packages(&removed, &remaining, &options)?;
+
+    Ok(())
+}
+
+/// Works out what removing the components would delete and uninstall, warning about remaining
+/// components that depend on them instead of asking.
+fn plan_remove(
+    removed: &[RemovedComponent],
+    remaining: &[&InstalledComponent],
+    options: &RemoveSchema,
+    config: &Config,
+) -> Result<Plan, RemoveError> {
+    let mut plan = Plan::new("remove");
+
+    for component in removed {
+        let dependents = dependents(component, remaining);
+
+        if !dependents.is_empty() && !options.force {
+            warn!(
+                "{} is required by {dependents}, removing it needs confirmation or --force",
+                component.name
+            );
+        }
+
+        for path in component.files.iter().filter(|path| path.is_file()) {
+            plan.file(path, FileAction::Delete, Some(&component.name), config);
+        }
+
+        plan.components.push(component.name.clone());
+    }
+
+    let lockfile = project_root(config).join(LOCKFILE_NAME);
+
+    if lockfile.is_file() {
+        plan.file(&lockfile, FileAction::Update, None, config);
+    }
+
+    // Unused packages are only uninstalled when confirmed, which can't happen without a terminal.
+    if options.yes || user_attended() {
+        plan.uninstall = unused_packages(removed, remaining, options);
+    }
+
+    Ok(plan)
+}
+
+fn dependents(component: &RemovedComponent, remaining: &[&InstalledComponent]) -> String {
+    remaining
+        .iter()
+        .filter(|installed| component.is_required_by(installed))
+        .map(|installed| installed.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Checks whether any remaining component lists a removed one in its `registry_dependencies`
+/// and asks whether to continue anyway.
+fn confirm_dependents(
+    removed: &[RemovedComponent],
+    remaining: &[&InstalledComponent],
+    options: &RemoveSchema,
+) -> Result<bool, RemoveError> {
+    for component in removed {
+        let dependents = dependents(component, remaining);
+
+        if dependents.is_empty() {
+            continue;
+        }
+
+        if options.force {
+            warn!(
+                "Removing {} even though it is required by {dependents}",
+                component.name
+            );
+            continue;
+        }
+
+        if options.yes || !user_attended() {
+            return Err(RemoveError::RequiredBy(component.name.clone(), dependents));
+        }
+
+        let confirmation = Confirm::new()
+            .with_prompt(format!(
+                "{} is required by {}. Remove it anyway?",
+                style(&component.name).bold().cyan(),
+                style(&dependents).bold()
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirmation {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Offers to uninstall the npm packages of the removed components that no remaining component
+/// declares.
+fn uninstall_unused_packages(
+    removed: &[RemovedComponent],
+    remaining: &[&InstalledComponent],
+    options: &RemoveSchema,
+) -> Result<(), RemoveError> {
+    let unused = unused_packages(removed, remaining, options);
+
+    if unused.is_empty() {
+        return Ok(());
+    }
+
+    let confirmation = if options.yes {
+        true
+    } else if user_attended() {
+        Confirm::new()
+            .with_prompt(format!(
+                "Uninstall packages no longer used by any component ({})?",
+                style(unused.join(", ")).bold().cyan()
+            ))
+            .default(true)
+            .interact()?
+    } else {
+        false
+    };
+
+    if confirmation {
+        uninstall_packages(&options.cwd, &unused)?;
+    } else {
+        info!("Keeping packages {}", unused.join(", "));
+    }
+
+    Ok(())
+}
+
+/// The packages declared by the project that only the removed components use.
+fn unused_packages(
+    removed: &[RemovedComponent],
+    remaining: &[&InstalledComponent],
+    options: &RemoveSchema,
+) -> Vec<String> {
+    let still_needed = remaining
+        .iter()
+        .flat_map(|component| component.dependencies.iter().chain(&component.dev_dependencies))
+        .map(|spec| package_name(spec))
+        .collect::<BTreeSet<_>>();
+
+    let candidates = removed
+        .iter()
+        .flat_map(|component| &component.packages)
+        .map(|spec| package_name(spec))
+        .filter(|name| !still_needed.contains(name));
+
+    declared_packages(&options.cwd, candidates)
+}
+
+/// Removes the now empty directories left behind by a deleted file, stopping at the project
+/// and alias directories.
+fn remove_empty_parents(path: &Path, config: &Config) {
+    let paths = &config.resolved_paths;
+    let stops = [
+        &paths.cwd,
+        &paths.components,
+        &paths.ui,
+        &paths.utils,
+        &paths.hooks,
+        &paths.lib,
+    ];
+
+    let mut directory = path.parent();
+
+    while let Some(current) = directory {
+        if stops.iter().any(|stop| stop.as_deref() == Some(current)) {
+            break;
+        }
+
+        if fs::remove_dir(current).is_err() {
+            break;
+        }
+
+        directory = current.parent();
+    }
+}
@@ -0,0 +1,124 @@
+use crate::commands::list_command::{ITEM_HEADER, item_row};
+use crate::config::{Config, ConfigError};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::resolve_registry_index::resolve_registry_indexes;
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::print_table::print_table;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// How similar a query term and a word must be to count as a typo of each other.
+const MIN_SIMILARITY: f64 = 0.85;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("Passed in query was empty")]
+    QueryEmpty,
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchSchema {
+    pub cwd: PathBuf,
+    pub query: String,
+}
+
+pub async fn search_command(options: SearchSchema) -> Result<(), SearchError> {
+    let terms = options.query.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>();
+
+    if terms.is_empty() {
+        return Err(SearchError::QueryEmpty);
+    }
+
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+    let indexes = resolve_registry_indexes(&config).await;
+
+    let mut matches = indexes
+        .iter()
+        .flat_map(|(namespace, index)| {
+            index
+                .items
+                .iter()
+                .filter_map(|item| score_item(&terms, item).map(|score| (score, *namespace, item)))
+        })
+        .collect::<Vec<_>>();
+
+    if matches.is_empty() {
+        info!("No components match `{}`.", options.query);
+        return Ok(());
+    }
+
+    matches.sort_by(|(a, _, a_item), (b, _, b_item)| {
+        b.total_cmp(a).then_with(|| a_item.name.cmp(&b_item.name))
+    });
+
+    let rows = matches
+        .into_iter()
+        .map(|(_, namespace, item)| item_row(namespace, item))
+        .collect::<Vec<_>>();
+
+    print_table(ITEM_HEADER, &rows);
+
+    Ok(())
+}
+
+/// Scores an item against every term of the query, weighing matches in the name above the
+/// title and tags, and those above the description. Items missing any term score `None`.
+pub fn score_item(terms: &[String], item: &RegistryItem) -> Option<f64> {
+    let tags = item.tags.iter().flatten().map(String::as_str).collect::<Vec<_>>().join(" ");
+
+    let fields = [
+        (item.name.to_lowercase(), 3.0),
+        (item.title.to_lowercase(), 2.0),
+        (tags.to_lowercase(), 2.0),
+        (item.description.to_lowercase(), 1.0),
+    ];
+
+    terms.iter().try_fold(0.0, |total, term| {
+        let best = fields
+            .iter()
+            .map(|(field, weight)| weight * score_term(term, field))
+            .fold(0.0, f64::max);
+
+        (best > 0.0).then_some(total + best)
+    })
+}
+
+/// 1 for an exact match, slightly less for words starting with or containing the term, and
+/// a penalized similarity for likely typos.
+fn score_term(term: &str, field: &str) -> f64 {
+    if field == term {
+        return 1.0;
+    }
+
+    let words = field.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty());
+    let mut best: f64 = 0.0;
+
+    for word in words {
+        let score = if word == term {
+            0.95
+        } else if word.starts_with(term) {
+            0.9
+        } else if word.contains(term) {
+            0.75
+        } else {
+            let similarity = strsim::jaro_winkler(term, word);
+            if similarity >= MIN_SIMILARITY {
+                similarity * 0.6
+            } else {
+                0.0
+            }
+        };
+
+        best = best.max(score);
+    }
+
+    best
+}
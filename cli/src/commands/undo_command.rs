@@ -0,0 +1,148 @@
+use crate::lockfile::hash_content;
+use crate::util::journal::{JournalEntry, journal_root, read_journal, read_object, undone_entries};
+use crate::util::transaction::{Transaction, TransactionError};
+use console::{style, user_attended};
+use dialoguer::Confirm;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum UndoError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    PromptError(#[from] dialoguer::Error),
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
+    #[error("Nothing to undo, every recorded operation has already been undone")]
+    NothingToUndo,
+    #[error("No operation #{0} was found, see `lumina history`")]
+    NotFound(u64),
+    #[error("Operation #{0} has already been undone")]
+    AlreadyUndone(u64),
+    #[error("Refusing to undo #{0}, these files have changed since: {1}")]
+    Modified(u64, String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UndoSchema {
+    pub cwd: PathBuf,
+    pub id: Option<u64>,
+    pub yes: bool,
+}
+
+pub fn undo_command(options: UndoSchema) -> Result<(), UndoError> {
+    let root = &journal_root(&options.cwd)?;
+    let entries = read_journal(root)?;
+    let undone = undone_entries(&entries);
+
+    let entry = match options.id {
+        Some(id) => {
+            let entry =
+                entries.iter().find(|entry| entry.id == id).ok_or(UndoError::NotFound(id))?;
+
+            if undone.contains(&id) {
+                return Err(UndoError::AlreadyUndone(id));
+            }
+
+            entry
+        }
+        // Undos are only reverted when asked for by id, so repeating `undo` keeps going back.
+        None => entries
+            .iter()
+            .rev()
+            .find(|entry| entry.undoes.is_none() && !undone.contains(&entry.id))
+            .ok_or(UndoError::NothingToUndo)?,
+    };
+
+    let modified = modified_files(entry, root)?;
+
+    if !modified.is_empty() {
+        return Err(UndoError::Modified(entry.id, modified.join(", ")));
+    }
+
+    info!(
+        "{} #{} `lumina {}`:",
+        style("Undoing").bold(),
+        entry.id,
+        entry.command
+    );
+
+    for file in &entry.files {
+        let action = match (&file.before, &file.after) {
+            (None, _) => "delete",
+            (Some(_), None) => "restore",
+            (Some(_), Some(_)) => "revert",
+        };
+
+        info!("  {} {}", style(format!("{action:>7}")).bold(), file.path);
+    }
+
+    if !options.yes && user_attended() {
+        let confirmation = Confirm::new().with_prompt("Proceed?").default(true).interact()?;
+
+        if !confirmation {
+            error!("Aborting undo.");
+            return Ok(());
+        }
+    }
+
+    let mut tx = Transaction::begin(root);
+    tx.undoing(entry.id);
+
+    for file in &entry.files {
+        let target = file.target(root);
+
+        match &file.before {
+            Some(hash) => tx.write(&target, read_object(root, hash)?)?,
+            None => tx.remove(&target),
+        }
+    }
+
+    tx.commit()?;
+
+    for file in entry.files.iter().filter(|file| file.before.is_none()) {
+        remove_empty_parents(&file.target(root), root);
+    }
+
+    info!("{} #{}", style("Undid").bold(), entry.id);
+
+    Ok(())
+}
+
+/// The files of `entry` that no longer have the content it left.
+fn modified_files(entry: &JournalEntry, root: &Path) -> Result<Vec<String>, UndoError> {
+    let mut modified = Vec::new();
+
+    for file in &entry.files {
+        let current = match fs::read(file.target(root)) {
+            Ok(content) => Some(hash_content(content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        if current != file.after {
+            modified.push(file.path.clone());
+        }
+    }
+
+    Ok(modified)
+}
+
+fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut directory = path.parent();
+
+    while let Some(current) = directory {
+        if current == root || fs::remove_dir(current).is_err() {
+            break;
+        }
+
+        directory = current.parent();
+    }
+}
@@ -0,0 +1,325 @@
+use crate::config::{Config, ConfigError};
+use crate::lockfile::{
+    LOCKFILE_NAME, LockedComponent, Lockfile, LockfileError, hash_content, lock_key, lock_path,
+    project_root,
+};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::registry_source::{RegistrySource, split_version};
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::registry::resolve_registry_tree::resolve_registry_tree;
+use crate::util::install_packages::{InstallError, install_packages, missing_packages};
+use crate::util::journal::journal_root;
+use crate::util::merge_base::{
+    prune_merge_base, read_merge_base, remove_merge_base, write_merge_base,
+};
+use crate::util::plan::{FileAction, Plan};
+use crate::util::resolve_file_target_path::{
+    FileTargetError, ResolvedItemFile, resolve_item_files,
+};
+use crate::util::transaction::{Transaction, TransactionError};
+use console::style;
+use diffy::{ConflictStyle, MergeOptions};
+use indicatif::MultiProgress;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs, io::ErrorKind, path::PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    FileTargetError(#[from] FileTargetError),
+    #[error(transparent)]
+    LockfileError(#[from] LockfileError),
+    #[error(transparent)]
+    InstallError(#[from] InstallError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
+    #[error("No {LOCKFILE_NAME} was found, add components before updating them")]
+    NoLockfile,
+    #[error("`{0}` is not installed")]
+    NotInstalled(String),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateSchema {
+    pub cwd: PathBuf,
+    pub components: Vec<String>,
+    pub no_install: bool,
+    pub concurrency: usize,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+/// What happened to a single file during an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileUpdate {
+    Created,
+    Updated,
+    Merged,
+    Conflict,
+    Unchanged,
+    /// Dropped upstream and deleted, since it wasn't edited.
+    Removed,
+    /// Dropped upstream but kept, since it was edited.
+    Kept,
+}
+
+impl FileUpdate {
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Merged => "merged",
+            Self::Conflict => "conflict",
+            Self::Unchanged => "unchanged",
+            Self::Removed => "removed",
+            Self::Kept => "kept",
+        }
+    }
+
+    const fn action(&self) -> FileAction {
+        match self {
+            Self::Created => FileAction::Create,
+            Self::Updated => FileAction::Update,
+            Self::Merged => FileAction::Merge,
+            Self::Conflict => FileAction::Conflict,
+            Self::Unchanged => FileAction::Unchanged,
+            Self::Removed => FileAction::Delete,
+            Self::Kept => FileAction::Skip,
+        }
+    }
+}
+
+pub async fn update_command(mp: &MultiProgress, options: UpdateSchema) -> Result<(), UpdateError> {
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+    let root = project_root(&config);
+    let mut lockfile = Lockfile::read(&root)?.ok_or(UpdateError::NoLockfile)?;
+
+    let names = if options.components.is_empty() {
+        lockfile.components.keys().cloned().collect::<Vec<_>>()
+    } else {
+        options.components.clone()
+    };
+
+    let mut sources = Vec::new();
+
+    for component in &names {
+        // `button@^3` moves an installed component to another version or range.
+        let (name, version) = split_version(component);
+
+        let locked = lockfile
+            .components
+            .get(name)
+            .ok_or_else(|| UpdateError::NotInstalled(name.to_string()))?;
+
+        for source in RegistrySource::parse(&locked.source)? {
+            sources.push(match (version, &source) {
+                (Some(version), RegistrySource::Name(_)) => source.pinned(version),
+                // Only components added by name are published with versions to move between.
+                (Some(_), _) => return Err(RegistryError::Unversioned(name.to_string()).into()),
+                (None, _) => source,
+            });
+        }
+    }
+
+    let registry_items = resolve_registry_tree(&sources, &config, mp, options.concurrency).await?;
+
+    let mut report = Vec::new();
+    let mut dependencies = Vec::new();
+    let mut dev_dependencies = Vec::new();
+    let mut plan = Plan::new("update");
+    // Files upstream no longer ships, whose merge bases are removed.
+    let mut dropped = Vec::new();
+    let mut tx = Transaction::begin(&journal_root(&options.cwd)?);
+
+    for resolved in &registry_items {
+        let item = &resolved.item;
+        let key = lock_key(resolved);
+        let locked = lockfile.components.get(&key);
+
+        dependencies.extend(item.dependencies.iter().flatten().cloned());
+        dev_dependencies.extend(item.dev_dependencies.iter().flatten().cloned());
+
+        let files = resolve_item_files(item, &config)?;
+
+        for file in &files {
+            let locked_hash = locked.and_then(|locked| {
+                locked
+                    .files
+                    .get(&lock_path(config.resolved_paths.relative(&file.path)))
+                    .map(String::as_str)
+            });
+
+            let (status, content) = update_file(file, locked_hash, &config)?;
+
+            if options.dry_run {
+                plan.file(&file.path, status.action(), Some(&key), &config);
+                continue;
+            }
+
+            if let Some(content) = content {
+                tx.write(&file.path, content)?;
+            }
+
+            write_merge_base(&mut tx, &file.path, &file.content, &config)?;
+            report.push((file.path.clone(), status));
+        }
+
+        let current = files
+            .iter()
+            .map(|file| lock_path(config.resolved_paths.relative(&file.path)))
+            .collect::<HashSet<_>>();
+
+        for (path, hash) in locked.iter().flat_map(|locked| &locked.files) {
+            if current.contains(path) {
+                continue;
+            }
+
+            let target = root.join(path);
+            let status = match fs::read(&target) {
+                Ok(local) if hash_content(&local) == *hash => Some(FileUpdate::Removed),
+                Ok(_) => Some(FileUpdate::Kept),
+                Err(e) if e.kind() == ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+
+            if options.dry_run {
+                if let Some(status) = status {
+                    plan.file(&target, status.action(), Some(&key), &config);
+                }
+                continue;
+            }
+
+            if status == Some(FileUpdate::Removed) {
+                tx.remove(&target);
+            }
+
+            remove_merge_base(&mut tx, &target, &config);
+            dropped.push(target.clone());
+            report.extend(status.map(|status| (target, status)));
+        }
+
+        plan.components.push(key.clone());
+        lockfile
+            .components
+            .insert(key, LockedComponent::new(resolved, &files, &config)?);
+    }
+
+    let packages = missing_packages(&options.cwd, &dependencies, &dev_dependencies);
+
+    if options.dry_run {
+        plan.file(&root.join(LOCKFILE_NAME), FileAction::Update, None, &config);
+
+        if !options.no_install {
+            plan.install.dependencies = packages.dependencies;
+            plan.install.dev_dependencies = packages.dev_dependencies;
+        }
+
+        plan.print(options.json)?;
+        return Ok(());
+    }
+
+    lockfile.write(&mut tx, &root)?;
+    tx.commit()?;
+
+    for path in &dropped {
+        prune_merge_base(path, &config);
+    }
+
+    print_report(&report, &config);
+
+    if !options.no_install && !packages.is_empty() {
+        install_packages(&options.cwd, &packages)?;
+    }
+
+    Ok(())
+}
+
+/// Works out how to bring a single file up to date, merging upstream changes into local edits.
+/// Returns the new content of the file unless it stays as is. `locked_hash` is the hash recorded
+/// when the file was last installed.
+fn update_file(
+    file: &ResolvedItemFile,
+    locked_hash: Option<&str>,
+    config: &Config,
+) -> Result<(FileUpdate, Option<String>), UpdateError> {
+    let local = match fs::read_to_string(&file.path) {
+        Ok(local) => local,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok((FileUpdate::Created, Some(file.content.clone())));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if local == file.content {
+        return Ok((FileUpdate::Unchanged, None));
+    }
+
+    let base = read_merge_base(&file.path, config)?;
+
+    let locally_modified = match (&base, locked_hash) {
+        (Some(base), _) => *base != local,
+        (None, Some(hash)) => hash != hash_content(&local),
+        (None, None) => true,
+    };
+
+    if !locally_modified {
+        return Ok((FileUpdate::Updated, Some(file.content.clone())));
+    }
+
+    // Without a recorded base the whole file ends up as a single conflict for the user to resolve.
+    let base = base.unwrap_or_default();
+
+    let (merged, status) = match MergeOptions::new().set_conflict_style(ConflictStyle::Merge).merge(
+        &base,
+        &local,
+        &file.content,
+    ) {
+        Ok(merged) => (merged, FileUpdate::Merged),
+        Err(conflicted) => (conflicted, FileUpdate::Conflict),
+    };
+
+    Ok((status, Some(merged)))
+}
+
+fn print_report(report: &[(PathBuf, FileUpdate)], config: &Config) {
+    for (path, status) in report {
+        let label = style(format!("{:>9}", status.as_str()));
+        let label = match status {
+            FileUpdate::Conflict => label.red().bold(),
+            FileUpdate::Merged => label.yellow().bold(),
+            FileUpdate::Unchanged => label.dim(),
+            FileUpdate::Removed => label.red().bold(),
+            FileUpdate::Kept => label.yellow().bold(),
+            _ => label.green().bold(),
+        };
+
+        let path = config.resolved_paths.relative(path).display();
+
+        match status {
+            FileUpdate::Kept => info!("{label} {path} {}", style("(removed upstream, kept)").dim()),
+            _ => info!("{label} {path}"),
+        }
+    }
+
+    let conflicts = report.iter().filter(|(_, status)| *status == FileUpdate::Conflict).count();
+
+    if conflicts > 0 {
+        warn!("{conflicts} file(s) have conflicts, resolve the conflict markers before building.");
+    } else {
+        info!("All components are up to date.");
+    }
+}
@@ -0,0 +1,238 @@
+use crate::config::{Config, ConfigError};
+use crate::preflights::add::{PreflightAdd, preflight_project};
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::registry::resolve_registry_tree::{
+    DEFAULT_CONCURRENCY, ResolvedRegistryItem, resolve_registry_tree,
+};
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::highlight::highlight;
+use crate::util::install_packages::missing_packages;
+use crate::util::resolve_file_target_path::{
+    FileTargetError, ResolvedItemFile, resolve_item_files,
+};
+use console::style;
+use indicatif::MultiProgress;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::{fs, path::PathBuf};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum ViewError {
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    #[error(transparent)]
+    PreflightError(#[from] PreflightAdd),
+    #[error(transparent)]
+    RegistryError(#[from] RegistryError),
+    #[error(transparent)]
+    FileTargetError(#[from] FileTargetError),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewSchema {
+    pub cwd: PathBuf,
+    pub component: String,
+    pub contents: bool,
+}
+
+type DependencyGraph<'a> = HashMap<&'a RegistrySource, Vec<RegistrySource>>;
+
+pub async fn view_command(mp: &MultiProgress, options: ViewSchema) -> Result<(), ViewError> {
+    preflight_project(&options.cwd)?;
+
+    let config = Config::get_config()?;
+
+    let sources = RegistrySource::parse(&options.component)?;
+    let resolved = resolve_registry_tree(&sources, &config, mp, DEFAULT_CONCURRENCY).await?;
+
+    let graph = resolved
+        .iter()
+        .map(|resolved| {
+            let dependencies = resolved
+                .item
+                .registry_dependencies
+                .iter()
+                .flatten()
+                .map(|dependency| RegistrySource::parse_dependency(dependency, &resolved.source))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok((&resolved.source, dependencies))
+        })
+        .collect::<Result<DependencyGraph, RegistryError>>()?;
+
+    for source in &sources {
+        if let Some(root) = resolved.iter().find(|resolved| &resolved.source == source) {
+            print_metadata(root);
+        }
+    }
+
+    println!("\n{}", style("Registry dependencies").bold().underlined());
+
+    let mut shown = HashSet::new();
+
+    for source in &sources {
+        print_tree(source, &graph, "", &mut shown);
+    }
+
+    print_packages(&resolved, &options);
+
+    println!("\n{}", style("Files").bold().underlined());
+
+    for resolved in &resolved {
+        let files = resolve_item_files(&resolved.item, &config)?;
+
+        println!("{}", style(&resolved.source).bold());
+
+        for file in &files {
+            println!(
+                "  {} {}",
+                config.resolved_paths.relative(&file.path).display(),
+                file_status(file)
+            );
+        }
+
+        if options.contents {
+            for file in &files {
+                print_contents(file, &config);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_metadata(resolved: &ResolvedRegistryItem) {
+    let RegistryItem {
+        title,
+        item_type,
+        description,
+        version,
+        author,
+        tags,
+        ..
+    } = &resolved.item;
+
+    println!(
+        "{} {} {}{}",
+        style(title).bold().cyan(),
+        style(format!("({})", resolved.source)).dim(),
+        item_type,
+        version.as_ref().map(|version| format!(" v{version}")).unwrap_or_default()
+    );
+
+    if !description.is_empty() {
+        println!("  {description}");
+    }
+
+    if let Some(author) = author {
+        println!("  {} {author}", style("Author:").dim());
+    }
+
+    if let Some(tags) = tags.as_ref().filter(|tags| !tags.is_empty()) {
+        println!("  {} {}", style("Tags:").dim(), tags.join(", "));
+    }
+}
+
+/// Prints `source` and everything below it, expanding each item only the first time it shows up.
+fn print_tree(
+    source: &RegistrySource,
+    graph: &DependencyGraph,
+    prefix: &str,
+    shown: &mut HashSet<RegistrySource>,
+) {
+    if prefix.is_empty() {
+        println!("{source}");
+    }
+
+    if !shown.insert(source.clone()) {
+        return;
+    }
+
+    let dependencies = graph.get(source).map(Vec::as_slice).unwrap_or_default();
+
+    for (index, dependency) in dependencies.iter().enumerate() {
+        let last = index + 1 == dependencies.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        let repeated = shown.contains(dependency);
+
+        println!(
+            "{prefix}{branch}{dependency}{}",
+            if repeated { " (*)" } else { "" }
+        );
+
+        if !repeated {
+            print_tree(dependency, graph, &format!("{prefix}{indent}"), shown);
+        }
+    }
+}
+
+fn print_packages(resolved: &[ResolvedRegistryItem], options: &ViewSchema) {
+    let collect = |packages: fn(&RegistryItem) -> &Option<Vec<String>>| {
+        let mut collected = resolved
+            .iter()
+            .flat_map(|resolved| packages(&resolved.item).iter().flatten().cloned())
+            .collect::<Vec<_>>();
+        collected.sort();
+        collected.dedup();
+        collected
+    };
+
+    let dependencies = collect(|item| &item.dependencies);
+    let dev_dependencies = collect(|item| &item.dev_dependencies);
+
+    println!("\n{}", style("npm packages").bold().underlined());
+
+    if dependencies.is_empty() && dev_dependencies.is_empty() {
+        println!("{}", style("none").dim());
+        return;
+    }
+
+    let missing = missing_packages(&options.cwd, &dependencies, &dev_dependencies);
+
+    let packages = dependencies
+        .iter()
+        .map(|package| (package, "", missing.dependencies.contains(package)))
+        .chain(
+            dev_dependencies
+                .iter()
+                .map(|package| (package, "dev, ", missing.dev_dependencies.contains(package))),
+        );
+
+    for (package, kind, is_missing) in packages {
+        let status = if is_missing {
+            style(format!("({kind}will be installed)")).green()
+        } else {
+            style(format!("({kind}already installed)")).dim()
+        };
+
+        println!("{package} {status}");
+    }
+}
+
+fn file_status(file: &ResolvedItemFile) -> console::StyledObject<&'static str> {
+    match fs::read_to_string(&file.path) {
+        Ok(existing) if existing == file.content => style("(unchanged)").dim(),
+        Ok(_) => style("(differs from the local file)").yellow(),
+        Err(_) => style("(new)").green(),
+    }
+}
+
+fn print_contents(file: &ResolvedItemFile, config: &Config) {
+    println!(
+        "\n{}",
+        style(format!(
+            "── {} ──",
+            config.resolved_paths.relative(&file.path).display()
+        ))
+        .dim()
+    );
+    println!("{}", highlight(&file.content, &file.path));
+}
@@ -1,6 +1,8 @@
 use crate::util::get_project_info::get_project_info;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
 use std::{env::current_dir, fs, fs::File, io::BufReader};
 use thiserror::Error;
 
@@ -48,6 +50,39 @@ pub struct ResolvedPaths {
     pub lib: Option<PathBuf>,
 }
 
+impl Aliases {
+    /// Looks up an alias by its name, e.g. `ui` -> `@/components/ui`.
+    pub fn get(&self, alias: &str) -> Option<&str> {
+        match alias {
+            "components" => self.components.as_deref(),
+            "ui" => self.ui.as_deref(),
+            "utils" => self.utils.as_deref(),
+            "hooks" => self.hooks.as_deref(),
+            "lib" => self.lib.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl ResolvedPaths {
+    /// Makes `path` relative to the project directory for display purposes.
+    pub fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        self.cwd.as_ref().and_then(|cwd| path.strip_prefix(cwd).ok()).unwrap_or(path)
+    }
+
+    /// Looks up the directory an alias points at by the alias name, e.g. `ui`.
+    pub fn get(&self, alias: &str) -> Option<&Path> {
+        match alias {
+            "components" => self.components.as_deref(),
+            "ui" => self.ui.as_deref(),
+            "utils" => self.utils.as_deref(),
+            "hooks" => self.hooks.as_deref(),
+            "lib" => self.lib.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 impl Default for Aliases {
     #[inline(always)]
     fn default() -> Self {
@@ -75,6 +110,81 @@ impl Default for ResolvedPaths {
     }
 }
 
+/// A registry entry in components.json, either just the URL template or the template along with
+/// headers sent on every request to it. Header values may reference environment variables as
+/// `${NAME}` so tokens don't have to be committed.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum RegistryConfig {
+    Url(String),
+    Detailed {
+        url: String,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl RegistryConfig {
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Url(url) => url,
+            Self::Detailed { url, .. } => url,
+        }
+    }
+
+    pub fn headers(&self) -> Option<&BTreeMap<String, String>> {
+        match self {
+            Self::Url(_) => None,
+            Self::Detailed { headers, .. } => Some(headers),
+        }
+    }
+}
+
+// Header values usually carry credentials, so they are never printed.
+impl Debug for RegistryConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryConfig")
+            .field("url", &self.url())
+            .field(
+                "headers",
+                &self
+                    .headers()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, _)| (name, "<redacted>"))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+            .finish()
+    }
+}
+
+/// Network settings used when talking to registries. Each one can be overridden through the
+/// environment (`LUMINA_CONNECT_TIMEOUT`, `LUMINA_TIMEOUT`, `LUMINA_RETRIES` and
+/// `LUMINA_CA_FILE`), which is handy on build agents. Proxies are picked up from `HTTPS_PROXY`,
+/// `HTTP_PROXY` and `NO_PROXY`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpConfig {
+    /// Seconds to wait for a connection to be established.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    /// Seconds a request may take, reading the response included, before it is given up on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// How many times a failed request is retried.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// A PEM bundle of extra certificate authorities to trust, relative to the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<PathBuf>,
+}
+
+impl HttpConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 // Plans for new themes later on.
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Themes {
@@ -100,6 +210,11 @@ impl Default for Themes {
 pub struct Config {
     pub theme: Themes,
     pub aliases: Aliases,
+    /// Namespaced registries, mapping e.g. `@acme` to `https://example.com/r/{name}.json`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, RegistryConfig>,
+    #[serde(default, skip_serializing_if = "HttpConfig::is_empty")]
+    pub http: HttpConfig,
     #[serde(skip)]
     pub resolved_paths: ResolvedPaths,
 }
@@ -0,0 +1,195 @@
+use crate::config::Config;
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_item::{RegistryError, parse_namespace};
+use crate::registry::resolve_registry_tree::ResolvedRegistryItem;
+use crate::util::resolve_file_target_path::ResolvedItemFile;
+use crate::util::transaction::Transaction;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+use thiserror::Error;
+
+pub const LOCKFILE_NAME: &str = "lumina-lock.json";
+pub const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum LockfileError {
+    #[error("Unsupported {LOCKFILE_NAME} version {0}, please update lumina")]
+    UnsupportedVersion(u32),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+}
+
+/// Records which components were installed into the project, where they came from and the
+/// hashes of the files that were written. Everything is kept in sorted maps so the file is
+/// stable across runs and diffs cleanly.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Lockfile {
+    pub lockfile_version: u32,
+    #[serde(default)]
+    pub components: BTreeMap<String, LockedComponent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedComponent {
+    /// What to pass to `lumina add` to get this component again.
+    pub source: String,
+    /// `default`, a configured namespace such as `@acme`, `url` or `file`.
+    pub registry: String,
+    /// The URL or project relative file the item was read from.
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registry_dependencies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dev_dependencies: Vec<String>,
+    /// Project relative file paths mapped to the SHA-256 of their installed content.
+    pub files: BTreeMap<String, String>,
+}
+
+impl Default for Lockfile {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            lockfile_version: LOCKFILE_VERSION,
+            components: BTreeMap::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Reads the lockfile of the project, returning `None` when there is none yet.
+    pub fn read(root: &Path) -> Result<Option<Lockfile>, LockfileError> {
+        let data = match fs::read_to_string(root.join(LOCKFILE_NAME)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let lockfile = serde_json::from_str::<Lockfile>(&data)?;
+
+        if lockfile.lockfile_version > LOCKFILE_VERSION {
+            return Err(LockfileError::UnsupportedVersion(lockfile.lockfile_version));
+        }
+
+        Ok(Some(lockfile))
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let mut data = serde_json::to_string_pretty(self)?;
+        data.push('\n');
+
+        Ok(data)
+    }
+
+    pub fn write(&self, tx: &mut Transaction, root: &Path) -> Result<(), LockfileError> {
+        tx.write(&root.join(LOCKFILE_NAME), self.to_json()?)?;
+
+        Ok(())
+    }
+}
+
+impl LockedComponent {
+    pub fn new(
+        resolved: &ResolvedRegistryItem,
+        files: &[ResolvedItemFile],
+        config: &Config,
+    ) -> Result<Self, LockfileError> {
+        let item = &resolved.item;
+        let root = project_root(config);
+
+        let (source, registry) = match &resolved.source {
+            RegistrySource::Name(name) => (
+                name.clone(),
+                parse_namespace(name)
+                    .map(|(namespace, _)| namespace.to_string())
+                    .unwrap_or_else(|| String::from("default")),
+            ),
+            RegistrySource::Url(url) => (url.clone(), String::from("url")),
+            RegistrySource::File(path) => (
+                path.strip_prefix(&root)
+                    .map(|relative| format!("./{}", lock_path(relative)))
+                    .unwrap_or_else(|_| path.to_string_lossy().to_string()),
+                String::from("file"),
+            ),
+        };
+
+        let url = match (&resolved.source, &item.version) {
+            // Files are recorded relative to the project, so the lockfile is the same everywhere.
+            (RegistrySource::File(_), _) => source.clone(),
+            // Ranges are recorded at the version they resolved to.
+            (named, Some(version)) if named.version().is_some() => {
+                named.pinned(version).location(config)?
+            }
+            (named, _) => named.location(config)?,
+        };
+
+        Ok(Self {
+            source,
+            registry,
+            url,
+            version: item.version.clone(),
+            registry_dependencies: item.registry_dependencies.clone().unwrap_or_default(),
+            dependencies: item.dependencies.clone().unwrap_or_default(),
+            dev_dependencies: item.dev_dependencies.clone().unwrap_or_default(),
+            files: files
+                .iter()
+                .map(|file| {
+                    (
+                        lock_path(config.resolved_paths.relative(&file.path)),
+                        hash_content(&file.content),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    /// Absolute paths of the files recorded for this component.
+    pub fn file_paths(&self, config: &Config) -> Vec<PathBuf> {
+        let root = project_root(config);
+
+        self.files.keys().map(|file| root.join(file)).collect()
+    }
+}
+
+/// The key a resolved item is recorded under, the name it was added by for registry items and
+/// the item name for urls and files.
+pub fn lock_key(resolved: &ResolvedRegistryItem) -> String {
+    match &resolved.source {
+        RegistrySource::Name(_) => resolved.source.unversioned().to_string(),
+        _ => resolved.item.name.clone(),
+    }
+}
+
+pub fn hash_content(content: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+pub fn project_root(config: &Config) -> PathBuf {
+    config.resolved_paths.cwd.clone().unwrap_or_default()
+}
+
+/// Joins the components of a relative path with `/` so the lockfile is the same on every OS.
+pub fn lock_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,20 +1,34 @@
 use crate::commands::build_command::{BuildSchema, build_command};
+use crate::commands::cache_command::{CacheAction, CacheSchema, cache_command};
+use crate::commands::diff_command::{DiffSchema, diff_command};
+use crate::commands::history_command::{HistorySchema, history_command};
+use crate::commands::list_command::{ListSchema, list_command};
+use crate::commands::outdated_command::{OutdatedSchema, outdated_command};
+use crate::commands::remove_command::{RemoveSchema, remove_command};
+use crate::commands::search_command::{SearchSchema, search_command};
+use crate::commands::undo_command::{UndoSchema, undo_command};
+use crate::commands::update_command::{UpdateSchema, update_command};
+use crate::commands::view_command::{ViewSchema, view_command};
 use crate::commands::{
     add_command::{AddSchema, add_command},
     init_command::{InitSchema, init_command},
 };
+use crate::registry::registry_cache::set_offline;
+use crate::registry::resolve_registry_tree::DEFAULT_CONCURRENCY;
+use crate::util::transaction::watch_interrupts;
 use cfg_if::cfg_if;
 use clap::{ArgAction, Parser, Subcommand, ValueHint};
 use indicatif::MultiProgress;
 use indicatif_log_bridge::LogWrapper;
 use log::error;
-use reqwest::Client;
-use std::{path::PathBuf, sync::LazyLock};
+use std::path::PathBuf;
 use thiserror::Error;
 
 mod commands;
 mod config;
+mod lockfile;
 mod preflights;
+mod registry;
 mod schemas;
 mod util;
 
@@ -42,6 +56,39 @@ pub enum MainError {
 
     #[error(transparent)]
     BuildError(#[from] commands::build_command::BuildError),
+
+    #[error(transparent)]
+    DiffError(#[from] commands::diff_command::DiffError),
+
+    #[error(transparent)]
+    RemoveError(#[from] commands::remove_command::RemoveError),
+
+    #[error(transparent)]
+    UpdateError(#[from] commands::update_command::UpdateError),
+
+    #[error(transparent)]
+    OutdatedError(#[from] commands::outdated_command::OutdatedError),
+
+    #[error(transparent)]
+    CacheError(#[from] commands::cache_command::CacheError),
+
+    #[error(transparent)]
+    ListError(#[from] commands::list_command::ListError),
+
+    #[error(transparent)]
+    SearchError(#[from] commands::search_command::SearchError),
+
+    #[error(transparent)]
+    ViewError(#[from] commands::view_command::ViewError),
+
+    #[error(transparent)]
+    HistoryError(#[from] commands::history_command::HistoryError),
+
+    #[error(transparent)]
+    UndoError(#[from] commands::undo_command::UndoError),
+
+    #[error("Interrupted, no changes were made")]
+    Interrupted,
 }
 
 #[derive(Parser)]
@@ -49,10 +96,10 @@ pub enum MainError {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[arg(long, global = true, action = ArgAction::SetTrue, help = "Whether or not to resolve registry items from the cache only, without touching the network")]
+    offline: bool,
 }
 
-pub static HTTPCLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-
 #[derive(Subcommand)]
 enum Commands {
     #[command(
@@ -72,8 +119,26 @@ enum Commands {
     Add {
         #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory you want to init into")]
         cwd: PathBuf,
-        #[arg(help = "names or urls of components you want to add")]
+        #[arg(
+            help = "names (optionally at a version or semver range, e.g. button@2.1.0 or button@^2), urls, registry item json files or built registry directories of the components you want to add, picks them interactively when omitted"
+        )]
         components: Vec<String>,
+        #[arg(long, action = ArgAction::SetTrue, conflicts_with = "components", help = "Whether or not to add every component of the configured registries")]
+        all: bool,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to skip installing the npm dependencies of the components")]
+        no_install: bool,
+        #[arg(long, action = ArgAction::SetTrue, conflicts_with = "skip_existing", help = "Whether or not to overwrite existing files without asking")]
+        overwrite: bool,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to keep existing files without asking")]
+        skip_existing: bool,
+        #[arg(short, long, action = ArgAction::SetTrue, help = "Whether or not to skip all prompts, existing files are kept unless --overwrite is passed")]
+        yes: bool,
+        #[arg(long, default_value_t = DEFAULT_CONCURRENCY, help = "How many registry items to fetch at the same time")]
+        concurrency: usize,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to only print what would change, without touching any files")]
+        dry_run: bool,
+        #[arg(long, action = ArgAction::SetTrue, requires = "dry_run", help = "Whether or not to print the dry run plan as json")]
+        json: bool,
     },
     Build {
         #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory you want to build from")]
@@ -83,6 +148,115 @@ enum Commands {
         #[arg(help = "destination directory for json files", value_hint = ValueHint::DirPath, default_value = "./public/r", short, long)]
         output: PathBuf,
     },
+    #[command(about = "Compares installed component(s) against the registry")]
+    Diff {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project to compare")]
+        cwd: PathBuf,
+        #[arg(
+            help = "name or url of the component to compare, lists every component with upstream changes when omitted"
+        )]
+        component: Option<String>,
+    },
+    #[command(about = "Removes the desired component(s) from the project")]
+    Remove {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory you want to remove from")]
+        cwd: PathBuf,
+        #[arg(help = "names or urls of components you want to remove")]
+        components: Vec<String>,
+        #[arg(short, long, action = ArgAction::SetTrue, help = "Whether or not to remove components that other installed components depend on")]
+        force: bool,
+        #[arg(short, long, action = ArgAction::SetTrue, help = "Whether or not to skip prompts and uninstall npm dependencies no longer in use")]
+        yes: bool,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to only print what would change, without touching any files")]
+        dry_run: bool,
+        #[arg(long, action = ArgAction::SetTrue, requires = "dry_run", help = "Whether or not to print the dry run plan as json")]
+        json: bool,
+    },
+    #[command(
+        about = "Updates installed component(s) to the latest registry version, merging in local changes"
+    )]
+    Update {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory you want to update in")]
+        cwd: PathBuf,
+        #[arg(
+            help = "names of the installed components you want to update, optionally at a new version or semver range, updates every component when omitted"
+        )]
+        components: Vec<String>,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to skip installing new npm dependencies of the components")]
+        no_install: bool,
+        #[arg(long, default_value_t = DEFAULT_CONCURRENCY, help = "How many registry items to fetch at the same time")]
+        concurrency: usize,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to only print what would change, without touching any files")]
+        dry_run: bool,
+        #[arg(long, action = ArgAction::SetTrue, requires = "dry_run", help = "Whether or not to print the dry run plan as json")]
+        json: bool,
+    },
+    #[command(about = "Lists installed components that have a newer version in the registry")]
+    Outdated {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project to check")]
+        cwd: PathBuf,
+    },
+    #[command(about = "Lists every component in the configured registries")]
+    List {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project whose registries to list")]
+        cwd: PathBuf,
+    },
+    #[command(about = "Searches the configured registries by name, title, description and tags")]
+    Search {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project whose registries to search")]
+        cwd: PathBuf,
+        #[arg(required = true, help = "words to look for, typos are tolerated")]
+        query: Vec<String>,
+    },
+    #[command(
+        about = "Shows what adding a component would do: its metadata, dependencies, packages and files"
+    )]
+    View {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project the component would be added to")]
+        cwd: PathBuf,
+        #[arg(help = "name, url or registry item json file of the component to inspect")]
+        component: String,
+        #[arg(long, action = ArgAction::SetTrue, help = "Whether or not to print the contents of every file")]
+        contents: bool,
+    },
+    #[command(about = "Manages the cache of fetched registry items")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    #[command(about = "Lists the operations recorded in the project's journal, newest first")]
+    History {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project whose history to show")]
+        cwd: PathBuf,
+    },
+    #[command(
+        about = "Reverts the latest operation, or the one with the given id from `lumina history`"
+    )]
+    Undo {
+        #[arg(value_hint = ValueHint::DirPath, default_value = ".", short, long, help = "Directory of the project to undo an operation in")]
+        cwd: PathBuf,
+        #[arg(help = "id of the operation to revert, the latest one when omitted")]
+        id: Option<u64>,
+        #[arg(short, long, action = ArgAction::SetTrue, help = "Whether or not to skip the confirmation prompt")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    #[command(about = "Lists the cached registry responses")]
+    List,
+    #[command(about = "Removes every cached registry response")]
+    Clean,
+    #[command(about = "Removes cached registry responses that haven't been revalidated recently")]
+    Prune {
+        #[arg(
+            long,
+            default_value_t = 30,
+            help = "Remove responses older than this many days"
+        )]
+        max_age_days: u64,
+    },
 }
 
 async fn run() -> Result<(), MainError> {
@@ -96,6 +270,18 @@ async fn run() -> Result<(), MainError> {
     LogWrapper::new(mp.clone(), logger).try_init()?;
     log::set_max_level(level);
 
+    set_offline(cli.offline);
+    watch_interrupts()?;
+
+    // Dropping the command on Ctrl-C throws away whatever it staged. Once it starts moving files
+    // into place it runs to the end, where the interrupt rolls everything back.
+    tokio::select! {
+        result = run_command(&cli, &mp) => result,
+        _ = tokio::signal::ctrl_c() => Err(MainError::Interrupted),
+    }
+}
+
+async fn run_command(cli: &Cli, mp: &MultiProgress) -> Result<(), MainError> {
     match &cli.command {
         Commands::Init {
             yes,
@@ -104,7 +290,7 @@ async fn run() -> Result<(), MainError> {
             skip_preflight,
         } => {
             init_command(
-                &mp,
+                mp,
                 InitSchema {
                     yes: *yes,
                     force: *force,
@@ -113,11 +299,33 @@ async fn run() -> Result<(), MainError> {
                 },
             )?;
         }
-        Commands::Add { cwd, components } => {
-            add_command(AddSchema {
-                cwd: cwd.clone(),
-                components: components.clone(),
-            })
+        Commands::Add {
+            cwd,
+            components,
+            no_install,
+            overwrite,
+            skip_existing,
+            yes,
+            all,
+            concurrency,
+            dry_run,
+            json,
+        } => {
+            add_command(
+                mp,
+                AddSchema {
+                    cwd: cwd.clone(),
+                    components: components.clone(),
+                    no_install: *no_install,
+                    overwrite: *overwrite,
+                    skip_existing: *skip_existing,
+                    yes: *yes,
+                    all: *all,
+                    concurrency: *concurrency,
+                    dry_run: *dry_run,
+                    json: *json,
+                },
+            )
             .await?
         }
         Commands::Build {
@@ -129,6 +337,91 @@ async fn run() -> Result<(), MainError> {
             registry: registry.clone(),
             output: output.clone(),
         })?,
+        Commands::Diff { cwd, component } => {
+            diff_command(DiffSchema {
+                cwd: cwd.clone(),
+                component: component.clone(),
+            })
+            .await?
+        }
+        Commands::Remove {
+            cwd,
+            components,
+            force,
+            yes,
+            dry_run,
+            json,
+        } => {
+            remove_command(RemoveSchema {
+                cwd: cwd.clone(),
+                components: components.clone(),
+                force: *force,
+                yes: *yes,
+                dry_run: *dry_run,
+                json: *json,
+            })
+            .await?
+        }
+        Commands::Update {
+            cwd,
+            components,
+            no_install,
+            concurrency,
+            dry_run,
+            json,
+        } => {
+            update_command(
+                mp,
+                UpdateSchema {
+                    cwd: cwd.clone(),
+                    components: components.clone(),
+                    no_install: *no_install,
+                    concurrency: *concurrency,
+                    dry_run: *dry_run,
+                    json: *json,
+                },
+            )
+            .await?
+        }
+        Commands::Outdated { cwd } => outdated_command(OutdatedSchema { cwd: cwd.clone() }).await?,
+        Commands::List { cwd } => list_command(ListSchema { cwd: cwd.clone() }).await?,
+        Commands::Search { cwd, query } => {
+            search_command(SearchSchema {
+                cwd: cwd.clone(),
+                query: query.join(" "),
+            })
+            .await?
+        }
+        Commands::View {
+            cwd,
+            component,
+            contents,
+        } => {
+            view_command(
+                mp,
+                ViewSchema {
+                    cwd: cwd.clone(),
+                    component: component.clone(),
+                    contents: *contents,
+                },
+            )
+            .await?
+        }
+        Commands::Cache { command } => cache_command(CacheSchema {
+            action: match command {
+                CacheCommands::List => CacheAction::List,
+                CacheCommands::Clean => CacheAction::Clean,
+                CacheCommands::Prune { max_age_days } => CacheAction::Prune {
+                    max_age_days: *max_age_days,
+                },
+            },
+        })?,
+        Commands::History { cwd } => history_command(HistorySchema { cwd: cwd.clone() })?,
+        Commands::Undo { cwd, id, yes } => undo_command(UndoSchema {
+            cwd: cwd.clone(),
+            id: *id,
+            yes: *yes,
+        })?,
     }
 
     Ok(())
@@ -137,7 +430,10 @@ async fn run() -> Result<(), MainError> {
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
     if let Err(e) = run().await {
-        error!("{:#?}", e);
-        std::process::exit(1);
+        error!("{e}");
+        std::process::exit(match e {
+            MainError::Interrupted => 130,
+            _ => 1,
+        });
     }
 }
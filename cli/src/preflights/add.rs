@@ -1,5 +1,5 @@
 use crate::commands::add_command::AddSchema;
-use std::fs;
+use std::{fs, path::Path};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,15 +15,20 @@ pub enum PreflightAdd {
 }
 
 pub fn preflight_add(options: &AddSchema) -> Result<(), PreflightAdd> {
-    if !fs::exists(&options.cwd)? {
+    preflight_project(&options.cwd)
+}
+
+/// Checks shared by every command that operates on an initialized project.
+pub fn preflight_project(cwd: &Path) -> Result<(), PreflightAdd> {
+    if !fs::exists(cwd)? {
         return Err(PreflightAdd::MissingCWD);
     }
 
-    if !fs::exists(options.cwd.join("package.json"))? {
+    if !fs::exists(cwd.join("package.json"))? {
         return Err(PreflightAdd::PackageJsonNotFound);
     }
 
-    if !fs::exists(options.cwd.join("components.json"))? {
+    if !fs::exists(cwd.join("components.json"))? {
         return Err(PreflightAdd::ComponentsJsonMissing);
     }
 
@@ -0,0 +1,177 @@
+use crate::config::Config;
+use crate::registry::http_client::{registry_client, request_error, status_error};
+use crate::registry::registry_cache::{
+    CachedResponse, is_offline, read_cached, unix_now, write_cached,
+};
+use crate::registry::resolve_registry_item::{RegistryError, parse_registry_json};
+use log::warn;
+use reqwest::StatusCode;
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::de::DeserializeOwned;
+use std::env;
+
+/// Fetches and decodes a json document from a registry using the shared client, attaching the
+/// headers configured for the registry the URL belongs to. Transient failures are retried.
+pub async fn fetch_registry_json<T: DeserializeOwned>(
+    url: &str,
+    config: &Config,
+) -> Result<T, RegistryError> {
+    parse_registry_json(&fetch_registry_body(url, config).await?, url)
+}
+
+/// Returns the body of a registry document, going through the cache: stored responses are
+/// revalidated with the registry, used as is in offline mode and used as a fallback when the
+/// registry can't be reached.
+async fn fetch_registry_body(url: &str, config: &Config) -> Result<String, RegistryError> {
+    if is_offline() {
+        return read_cached(url)?
+            .map(|cached| cached.body)
+            .ok_or_else(|| RegistryError::NotCached(url.to_string()));
+    }
+
+    let cached = read_cached(url).unwrap_or_else(|e| {
+        warn!("Could not read the cached copy of `{url}`: {e}");
+        None
+    });
+
+    match fetch_from_registry(url, cached.as_ref(), config).await {
+        Ok(Some(response)) => {
+            if let Err(e) = write_cached(&response) {
+                warn!("Could not cache `{url}`: {e}");
+            }
+
+            Ok(response.body)
+        }
+        Ok(None) => {
+            let mut cached = cached.ok_or_else(|| RegistryError::NotCached(url.to_string()))?;
+            cached.fetched_at = unix_now();
+
+            if let Err(e) = write_cached(&cached) {
+                warn!("Could not cache `{url}`: {e}");
+            }
+
+            Ok(cached.body)
+        }
+        Err(
+            e @ (RegistryError::HttpError(_)
+            | RegistryError::Timeout(_)
+            | RegistryError::ServerError(..)),
+        ) => match cached {
+            Some(cached) => {
+                warn!("Could not reach the registry ({e}), using the cached copy of `{url}`");
+                Ok(cached.body)
+            }
+            None => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Requests a document, conditionally when a cached copy exists. Returns `None` when the registry
+/// reports that the cached copy is still current.
+async fn fetch_from_registry(
+    url: &str,
+    cached: Option<&CachedResponse>,
+    config: &Config,
+) -> Result<Option<CachedResponse>, RegistryError> {
+    let mut headers = registry_headers(url, config)?;
+
+    if let Some(cached) = cached {
+        let validators = [
+            (IF_NONE_MATCH, &cached.etag),
+            (IF_MODIFIED_SINCE, &cached.last_modified),
+        ];
+
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|value| value.parse().ok()) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    let response = registry_client(config)?.get(url, headers).await?;
+
+    let status = response.status();
+
+    if status == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(None);
+    }
+
+    if let Some(e) = status_error(url, status) {
+        return Err(e);
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .map(String::from)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    let body = response.text().await.map_err(|e| request_error(url, e))?;
+
+    Ok(Some(CachedResponse {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        fetched_at: unix_now(),
+        body,
+    }))
+}
+
+/// Builds the headers of the configured registry whose URL template is the longest prefix of
+/// `url`. Values are marked sensitive so they never show up in debug output.
+fn registry_headers(url: &str, config: &Config) -> Result<HeaderMap, RegistryError> {
+    let registry = config
+        .registries
+        .values()
+        .filter_map(|registry| {
+            let prefix = registry.url().split("{name}").next().unwrap_or_default();
+            (!prefix.is_empty() && url.starts_with(prefix)).then_some((prefix.len(), registry))
+        })
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, registry)| registry);
+
+    let mut headers = HeaderMap::new();
+
+    for (name, value) in registry.and_then(|registry| registry.headers()).into_iter().flatten() {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| RegistryError::InvalidHeader(name.clone()))?;
+        let mut header_value = HeaderValue::from_str(&expand_env_vars(value)?)
+            .map_err(|_| RegistryError::InvalidHeader(name.clone()))?;
+
+        header_value.set_sensitive(true);
+        headers.insert(header_name, header_value);
+    }
+
+    Ok(headers)
+}
+
+/// Replaces every `${NAME}` in `value` with the environment variable `NAME`.
+fn expand_env_vars(value: &str) -> Result<String, RegistryError> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        let name = &rest[start + 2..start + end];
+        let variable =
+            env::var(name).map_err(|_| RegistryError::MissingEnvVar(name.to_string()))?;
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&variable);
+        rest = &rest[start + end + 1..];
+    }
+
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
@@ -0,0 +1,327 @@
+use crate::config::Config;
+use crate::registry::resolve_registry_item::RegistryError;
+use log::warn;
+use reqwest::header::HeaderMap;
+use reqwest::{Certificate, Client, Response, StatusCode};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::{env, fs, str::FromStr, time::Duration};
+
+const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_RETRIES: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+static REGISTRY_CLIENT: OnceLock<RegistryClient> = OnceLock::new();
+
+/// The http client shared by every registry request, along with how often requests are retried.
+#[derive(Debug)]
+pub struct RegistryClient {
+    client: Client,
+    retries: u32,
+}
+
+/// The network settings from components.json with the environment overrides applied.
+#[derive(Debug)]
+struct HttpSettings {
+    connect_timeout: Duration,
+    timeout: Duration,
+    retries: u32,
+    ca_file: Option<PathBuf>,
+}
+
+/// Returns the shared registry client, building it from the project's settings on first use.
+pub fn registry_client(config: &Config) -> Result<&'static RegistryClient, RegistryError> {
+    if let Some(client) = REGISTRY_CLIENT.get() {
+        return Ok(client);
+    }
+
+    let client = RegistryClient::new(&HttpSettings::resolve(config)?)?;
+
+    Ok(REGISTRY_CLIENT.get_or_init(|| client))
+}
+
+impl RegistryClient {
+    fn new(settings: &HttpSettings) -> Result<Self, RegistryError> {
+        // Proxies are read from `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` by reqwest itself.
+        let mut builder = Client::builder()
+            .user_agent(concat!("lumina/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(settings.connect_timeout)
+            .timeout(settings.timeout);
+
+        if let Some(ca_file) = &settings.ca_file {
+            let pem = fs::read(ca_file)
+                .map_err(|e| RegistryError::CaFileUnreadable(ca_file.clone(), e))?;
+            let certificates = Certificate::from_pem_bundle(&pem)
+                .ok()
+                .filter(|certificates| !certificates.is_empty())
+                .ok_or_else(|| RegistryError::InvalidCaFile(ca_file.clone()))?;
+
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            retries: settings.retries,
+        })
+    }
+
+    /// Sends a GET request, retrying with exponential backoff on connection failures, timeouts
+    /// and responses that are likely to succeed later (5xx, 408 and 429).
+    pub async fn get(&self, url: &str, headers: HeaderMap) -> Result<Response, RegistryError> {
+        let mut attempt = 0;
+
+        loop {
+            let result = self.client.get(url).headers(headers.clone()).send().await;
+
+            let reason = match &result {
+                Ok(response) if is_retryable_status(response.status()) => {
+                    response.status().to_string()
+                }
+                Err(e) if e.is_timeout() => String::from("timed out"),
+                Err(e) if e.is_connect() => String::from("could not connect"),
+                _ => return result.map_err(|e| request_error(url, e)),
+            };
+
+            if attempt >= self.retries {
+                return result.map_err(|e| request_error(url, e));
+            }
+
+            let backoff =
+                INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF);
+
+            attempt += 1;
+            warn!(
+                "Request to `{url}` failed ({reason}), retrying in {}ms ({attempt}/{})",
+                backoff.as_millis(),
+                self.retries
+            );
+
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+impl HttpSettings {
+    fn resolve(config: &Config) -> Result<Self, RegistryError> {
+        let http = &config.http;
+
+        let connect_timeout = env_override("LUMINA_CONNECT_TIMEOUT")?
+            .or(http.connect_timeout)
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        let timeout = env_override("LUMINA_TIMEOUT")?.or(http.timeout).unwrap_or(DEFAULT_TIMEOUT);
+        let retries = env_override("LUMINA_RETRIES")?.or(http.retries).unwrap_or(DEFAULT_RETRIES);
+
+        // A path from the environment is taken as is, one from components.json is relative to
+        // the project.
+        let ca_file = match env::var_os("LUMINA_CA_FILE").filter(|value| !value.is_empty()) {
+            Some(path) => Some(PathBuf::from(path)),
+            None => http.ca_file.as_ref().map(|path| match &config.resolved_paths.cwd {
+                Some(cwd) => cwd.join(path),
+                None => path.clone(),
+            }),
+        };
+
+        Ok(Self {
+            connect_timeout: Duration::from_secs(connect_timeout),
+            timeout: Duration::from_secs(timeout),
+            retries,
+            ca_file,
+        })
+    }
+}
+
+fn env_override<T: FromStr>(name: &str) -> Result<Option<T>, RegistryError> {
+    match env::var(name) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.trim().parse().map(Some).map_err(|_| RegistryError::InvalidEnvVar {
+                name: name.to_string(),
+                value,
+            })
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The error a registry response status stands for, `None` when the request succeeded.
+pub fn status_error(url: &str, status: StatusCode) -> Option<RegistryError> {
+    let url = url.to_string();
+
+    match status {
+        _ if status.is_success() => None,
+        StatusCode::NOT_FOUND => Some(RegistryError::NotFound(url)),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Some(RegistryError::Unauthorized(url, status.as_u16()))
+        }
+        _ if status.is_server_error() => Some(RegistryError::ServerError(url, status.as_u16())),
+        _ => Some(RegistryError::UnexpectedStatus(url, status.as_u16())),
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+pub fn request_error(url: &str, error: reqwest::Error) -> RegistryError {
+    if error.is_timeout() {
+        RegistryError::Timeout(url.to_string())
+    } else {
+        RegistryError::HttpError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Instant;
+
+    enum Reply {
+        Status(u16),
+        /// Accepts the request and never answers it.
+        Hang,
+    }
+
+    /// Serves `replies` to one request each, returning the url and the number of requests made.
+    fn serve(replies: Vec<Reply>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/button.json", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for reply in replies {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+
+                let _ = stream.read(&mut [0; 4096]);
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                match reply {
+                    Reply::Status(status) => {
+                        let response = format!(
+                            "HTTP/1.1 {status} Status\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{{}}"
+                        );
+                        let _ = stream.write_all(response.as_bytes());
+                    }
+                    Reply::Hang => {
+                        thread::spawn(move || {
+                            thread::sleep(Duration::from_secs(5));
+                            drop(stream);
+                        });
+                    }
+                }
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn client(timeout: Duration, retries: u32) -> RegistryClient {
+        RegistryClient::new(&HttpSettings {
+            connect_timeout: Duration::from_secs(1),
+            timeout,
+            retries,
+            ca_file: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff_until_the_request_succeeds() {
+        let (url, requests) = serve(vec![
+            Reply::Status(503),
+            Reply::Status(429),
+            Reply::Status(200),
+        ]);
+        let started = Instant::now();
+
+        let response = client(Duration::from_secs(5), 3).get(&url, HeaderMap::new()).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(started.elapsed() >= INITIAL_BACKOFF * 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_configured_retries() {
+        let (url, requests) = serve(vec![
+            Reply::Status(500),
+            Reply::Status(502),
+            Reply::Status(200),
+        ]);
+
+        let response = client(Duration::from_secs(5), 1).get(&url, HeaderMap::new()).await.unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            status_error(&url, response.status()),
+            Some(RegistryError::ServerError(_, 502))
+        ));
+    }
+
+    /// Requests a document served with `status` once, checking that it isn't retried.
+    async fn request_once(status: u16) -> Option<RegistryError> {
+        let (url, requests) = serve(vec![Reply::Status(status), Reply::Status(200)]);
+
+        let response = client(Duration::from_secs(5), 3).get(&url, HeaderMap::new()).await.unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        status_error(&url, response.status())
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let error = request_once(404).await;
+        assert!(
+            matches!(error, Some(RegistryError::NotFound(_))),
+            "{error:?}"
+        );
+
+        let error = request_once(401).await;
+        assert!(
+            matches!(error, Some(RegistryError::Unauthorized(_, 401))),
+            "{error:?}"
+        );
+
+        let error = request_once(418).await;
+        assert!(
+            matches!(error, Some(RegistryError::UnexpectedStatus(_, 418))),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn times_out_and_retries_unanswered_requests() {
+        let (url, requests) = serve(vec![Reply::Hang, Reply::Hang]);
+        let started = Instant::now();
+
+        let error = client(Duration::from_millis(200), 1)
+            .get(&url, HeaderMap::new())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, RegistryError::Timeout(_)), "{error:?}");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn successful_statuses_are_not_errors() {
+        assert!(status_error("http://registry", StatusCode::OK).is_none());
+        assert!(matches!(
+            status_error("http://registry", StatusCode::FORBIDDEN),
+            Some(RegistryError::Unauthorized(_, 403))
+        ));
+    }
+}
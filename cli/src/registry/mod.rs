@@ -0,0 +1,8 @@
+pub mod fetch_registry;
+pub mod http_client;
+pub mod registry_cache;
+pub mod registry_source;
+pub mod resolve_registry_index;
+pub mod resolve_registry_item;
+pub mod resolve_registry_tree;
+pub mod resolve_registry_version;
@@ -0,0 +1,121 @@
+use crate::lockfile::hash_content;
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::util::get_cache_dir::get_cache_dir;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+const REGISTRY_CACHE_DIR: &str = "registry";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// A registry response kept on disk so it can be revalidated with its `ETag`/`Last-Modified`,
+/// or used as is when working offline.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedResponse {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Seconds since the unix epoch at which the registry last confirmed the body.
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+/// A file found in the cache directory, `None` when it could not be read as a cached response.
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub response: Option<CachedResponse>,
+}
+
+/// Makes every registry request resolve from the cache only.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+pub fn registry_cache_dir() -> Result<PathBuf, RegistryError> {
+    get_cache_dir()
+        .map(|dir| dir.join(REGISTRY_CACHE_DIR))
+        .ok_or(RegistryError::NoCacheDir)
+}
+
+fn cache_path(url: &str) -> Result<PathBuf, RegistryError> {
+    Ok(registry_cache_dir()?.join(format!("{}.json", hash_content(url))))
+}
+
+/// Reads the cached response for `url`, treating unreadable entries as missing.
+pub fn read_cached(url: &str) -> Result<Option<CachedResponse>, RegistryError> {
+    let data = match fs::read_to_string(cache_path(url)?) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(serde_json::from_str::<CachedResponse>(&data)
+        .ok()
+        .filter(|cached| cached.url == url))
+}
+
+pub fn write_cached(response: &CachedResponse) -> Result<(), RegistryError> {
+    let path = cache_path(&response.url)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string(response)?)?;
+
+    Ok(())
+}
+
+/// Lists every file in the registry cache, oldest first.
+pub fn cache_entries() -> Result<Vec<CacheEntry>, RegistryError> {
+    let dir = registry_cache_dir()?;
+
+    let read_dir = match fs::read_dir(&dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+
+    for entry in read_dir {
+        let path = entry?.path();
+
+        if !path.is_file() {
+            continue;
+        }
+
+        entries.push(CacheEntry {
+            size: fs::metadata(&path)?.len(),
+            response: read_entry(&path).ok(),
+            path,
+        });
+    }
+
+    entries.sort_by_key(|entry| entry.response.as_ref().map(|response| response.fetched_at));
+
+    Ok(entries)
+}
+
+fn read_entry(path: &Path) -> io::Result<CachedResponse> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,181 @@
+use crate::config::Config;
+use crate::registry::resolve_registry_item::{
+    RegistryError, parse_registry_json, resolve_registry_url,
+};
+use crate::registry::resolve_registry_version::{RequestedVersion, VERSIONS_INDEX};
+use crate::schemas::registry::Registry;
+use crate::util::resolve_file_target_path::normalize_path;
+use std::{
+    fmt::{Display, Formatter},
+    fs,
+    path::{self, Path, PathBuf},
+};
+
+/// Where a registry item is fetched from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RegistrySource {
+    /// A bare or namespaced name, e.g. `button` or `@acme/card`, optionally followed by `@` and
+    /// a version, a semver range or `latest`, e.g. `button@2.1.0` or `@acme/card@^2`.
+    Name(String),
+    /// A full URL to a registry item json file.
+    Url(String),
+    /// A registry item json file on disk.
+    File(PathBuf),
+}
+
+impl RegistrySource {
+    /// Parses a component passed on the command line. Directories are expanded into every
+    /// item listed in their `registry.json`, as produced by `lumina build`.
+    pub fn parse(component: &str) -> Result<Vec<Self>, RegistryError> {
+        if is_url(component) {
+            return Ok(vec![Self::Url(component.to_string())]);
+        }
+
+        if !looks_like_path(component) {
+            return Ok(vec![Self::Name(component.to_string())]);
+        }
+
+        let path = absolute_path(Path::new(component))?;
+
+        if path.is_dir() {
+            return read_registry_directory(&path);
+        }
+
+        if !path.is_file() {
+            return Err(RegistryError::LocalItemNotFound(path));
+        }
+
+        Ok(vec![Self::File(path)])
+    }
+
+    /// Parses an entry of `registry_dependencies`. Dependencies of local items are looked up
+    /// next to the item first so a built registry directory can be used end-to-end.
+    pub fn parse_dependency(dependency: &str, parent: &Self) -> Result<Self, RegistryError> {
+        if is_url(dependency) {
+            return Ok(Self::Url(dependency.to_string()));
+        }
+
+        if let Self::File(parent_path) = parent {
+            let base = parent_path.parent().unwrap_or(Path::new("."));
+
+            if looks_like_path(dependency) {
+                return Ok(Self::File(absolute_path(&base.join(dependency))?));
+            }
+
+            let sibling = base.join(format!("{dependency}.json"));
+
+            if sibling.is_file() {
+                return Ok(Self::File(sibling));
+            }
+        }
+
+        if looks_like_path(dependency) {
+            return Ok(Self::File(absolute_path(Path::new(dependency))?));
+        }
+
+        Ok(Self::Name(dependency.to_string()))
+    }
+
+    /// The URL or file path the item is read from. Names at a range or `latest` point to the
+    /// versions index the version is picked from.
+    pub fn location(&self, config: &Config) -> Result<String, RegistryError> {
+        match self {
+            Self::Name(component) => match split_version(component) {
+                (name, None) => resolve_registry_url(name, config),
+                // Versioned items are built into a directory named after the item.
+                (name, Some(version)) => {
+                    let file = match RequestedVersion::parse(component, version)? {
+                        RequestedVersion::Exact(version) => version.to_string(),
+                        _ => String::from(VERSIONS_INDEX),
+                    };
+
+                    resolve_registry_url(&format!("{name}/{file}"), config)
+                }
+            },
+            Self::Url(url) => Ok(url.clone()),
+            Self::File(path) => Ok(path.to_string_lossy().to_string()),
+        }
+    }
+
+    /// The version a named component was requested at.
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            Self::Name(component) => split_version(component).1,
+            _ => None,
+        }
+    }
+
+    /// Pins a named component to `version`, other sources are returned as is.
+    pub fn pinned(&self, version: &str) -> Self {
+        match self {
+            Self::Name(component) => {
+                Self::Name(format!("{}@{version}", split_version(component).0))
+            }
+            source => source.clone(),
+        }
+    }
+
+    /// The same source without a requested version.
+    pub fn unversioned(&self) -> Self {
+        match self {
+            Self::Name(component) => Self::Name(split_version(component).0.to_string()),
+            source => source.clone(),
+        }
+    }
+}
+
+impl Display for RegistrySource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Url(url) => write!(f, "{url}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Splits a component such as `button@2.1.0` or `@acme/card@^2` into its name and version.
+pub fn split_version(component: &str) -> (&str, Option<&str>) {
+    match component.rfind('@') {
+        Some(index) if index > 0 => (&component[..index], Some(&component[index + 1..])),
+        _ => (component, None),
+    }
+}
+
+fn is_url(component: &str) -> bool {
+    component.starts_with("https://") || component.starts_with("http://")
+}
+
+fn looks_like_path(component: &str) -> bool {
+    component.ends_with(".json")
+        || component.starts_with('.')
+        || Path::new(component).is_absolute()
+        || (!component.starts_with('@') && component.contains(['/', '\\']))
+}
+
+/// Makes `path` absolute and resolves its `.` and `..` components so the same file always maps
+/// to the same source.
+fn absolute_path(path: &Path) -> Result<PathBuf, RegistryError> {
+    let path = path::absolute(path)?;
+
+    Ok(normalize_path(&path).unwrap_or(path))
+}
+
+fn read_registry_directory(directory: &Path) -> Result<Vec<RegistrySource>, RegistryError> {
+    let index_path = directory.join("registry.json");
+
+    if !index_path.is_file() {
+        return Err(RegistryError::LocalItemNotFound(index_path));
+    }
+
+    let registry = parse_registry_json::<Registry>(
+        &fs::read_to_string(&index_path)?,
+        &index_path.to_string_lossy(),
+    )?;
+
+    Ok(registry
+        .items
+        .iter()
+        .map(|item| RegistrySource::File(directory.join(format!("{}.json", item.name))))
+        .collect())
+}
@@ -0,0 +1,58 @@
+use crate::config::Config;
+use crate::registry::fetch_registry::fetch_registry_json;
+use crate::registry::resolve_registry_item::{RegistryError, resolve_registry_url};
+use crate::schemas::registry::Registry;
+use log::warn;
+
+/// Name of the index file `lumina build` copies next to the built items.
+pub const REGISTRY_INDEX_NAME: &str = "registry";
+
+/// Fetches the `registry.json` index of the default registry, or of a configured namespace
+/// such as `@acme`.
+pub async fn resolve_registry_index(
+    namespace: Option<&str>,
+    config: &Config,
+) -> Result<Registry, RegistryError> {
+    let index = match namespace {
+        Some(namespace) => format!("{namespace}/{REGISTRY_INDEX_NAME}"),
+        None => REGISTRY_INDEX_NAME.to_string(),
+    };
+
+    let result =
+        fetch_registry_json::<Registry>(&resolve_registry_url(&index, config)?, config).await?;
+
+    Ok(result)
+}
+
+/// Fetches the index of the default registry and of every configured namespace, skipping the
+/// registries whose index can't be fetched.
+pub async fn resolve_registry_indexes(config: &Config) -> Vec<(Option<&str>, Registry)> {
+    let mut indexes = Vec::new();
+
+    for namespace in configured_namespaces(config) {
+        match resolve_registry_index(namespace, config).await {
+            Ok(index) => indexes.push((namespace, index)),
+            Err(e) => warn!(
+                "Skipping the {} registry, its index could not be fetched: {e}",
+                namespace.unwrap_or("default")
+            ),
+        }
+    }
+
+    indexes
+}
+
+/// The default registry followed by every namespace configured in components.json.
+pub fn configured_namespaces(config: &Config) -> Vec<Option<&str>> {
+    std::iter::once(None)
+        .chain(config.registries.keys().map(|namespace| Some(namespace.as_str())))
+        .collect()
+}
+
+/// The name used to add an item of the given namespace, e.g. `@acme/card`.
+pub fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{namespace}/{name}"),
+        None => name.to_string(),
+    }
+}
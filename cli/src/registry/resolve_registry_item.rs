@@ -0,0 +1,221 @@
+use crate::config::Config;
+use crate::registry::fetch_registry::fetch_registry_json;
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_index::{qualified_name, resolve_registry_index};
+use crate::registry::resolve_registry_version::resolve_registry_version;
+use crate::schemas::registry_item::RegistryItem;
+use indicatif::style::TemplateError;
+use serde::de::DeserializeOwned;
+use std::{fs, path::PathBuf};
+use thiserror::Error;
+
+pub const DEFAULT_REGISTRY_URL: &str = "https://lumina-ui.com/r/{name}.json";
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error(transparent)]
+    HttpError(#[from] reqwest::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("Circular registry dependency detected: {0}")]
+    DependencyCycle(String),
+    #[error("Unknown registry `{0}`, add it to the registries in your components.json")]
+    UnknownRegistry(String),
+    #[error("No registry item found at `{0}`")]
+    LocalItemNotFound(PathBuf),
+    #[error("The environment variable `{0}` used in the registry headers is not set")]
+    MissingEnvVar(String),
+    #[error("The registry header `{0}` has an invalid name or value")]
+    InvalidHeader(String),
+    #[error("Nothing was found at `{0}` (404)")]
+    NotFound(String),
+    #[error("Component `{name}` was not found in the registry.{}", format_suggestions(.suggestions))]
+    ComponentNotFound {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    #[error(
+        "Not authorized to access `{0}` ({1}), check the headers of the registry in your components.json"
+    )]
+    Unauthorized(String, u16),
+    #[error("The registry responded to `{0}` with a server error ({1}), try again later")]
+    ServerError(String, u16),
+    #[error("The registry responded to `{0}` with an unexpected status ({1})")]
+    UnexpectedStatus(String, u16),
+    #[error("Timed out while fetching `{0}`")]
+    Timeout(String),
+    #[error("The environment variable `{name}` must be a number, got `{value}`")]
+    InvalidEnvVar { name: String, value: String },
+    #[error("Could not read the CA bundle at `{0}`: {1}")]
+    CaFileUnreadable(PathBuf, #[source] std::io::Error),
+    #[error("`{0}` does not contain any PEM encoded certificates")]
+    InvalidCaFile(PathBuf),
+    #[error("`{0}` is not in the cache, run without --offline to fetch it")]
+    NotCached(String),
+    #[error("Could not find a cache directory, set LUMINA_CACHE_DIR to choose one")]
+    NoCacheDir,
+    #[error("Failed to fetch {} registry item(s):{}", .0.len(), format_failures(.0))]
+    FetchFailed(Vec<(String, RegistryError)>),
+    #[error(transparent)]
+    TemplateError(#[from] TemplateError),
+    #[error("`{0}` is not a valid version or semver range: {1}")]
+    InvalidVersion(String, String),
+    #[error("`{0}` is not published with versions, add it without `@version`")]
+    Unversioned(String),
+    #[error("No published version satisfies `{component}`, available: {}", .available.join(", "))]
+    NoMatchingVersion {
+        component: String,
+        available: Vec<String>,
+    },
+    #[error("Conflicting versions of `{component}` were requested: {}", requested.join(", "))]
+    IncompatibleVersions {
+        component: String,
+        requested: Vec<String>,
+    },
+    #[error("`{location}` is not a valid registry document, `{path}`: {message}")]
+    InvalidItemJson {
+        location: String,
+        path: String,
+        message: String,
+    },
+}
+
+fn format_failures(failures: &[(String, RegistryError)]) -> String {
+    failures
+        .iter()
+        .map(|(source, error)| format!("\n  - {source}: {error}"))
+        .collect()
+}
+
+fn format_suggestions(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [suggestion] => format!(" Did you mean `{suggestion}`?"),
+        suggestions => format!(
+            " Did you mean one of {}?",
+            suggestions
+                .iter()
+                .map(|suggestion| format!("`{suggestion}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+pub async fn resolve_registry_item(
+    source: &RegistrySource,
+    config: &Config,
+) -> Result<RegistryItem, RegistryError> {
+    if let RegistrySource::File(path) = source {
+        if !path.is_file() {
+            return Err(RegistryError::LocalItemNotFound(path.clone()));
+        }
+
+        return parse_registry_json(&fs::read_to_string(path)?, &path.to_string_lossy());
+    }
+
+    let source = resolve_registry_version(source, config).await?;
+
+    match fetch_registry_json::<RegistryItem>(&source.location(config)?, config).await {
+        Err(RegistryError::NotFound(_)) if matches!(source, RegistrySource::Name(_)) => {
+            // A missing version of an existing item isn't a typo.
+            let suggestions = match source.version() {
+                Some(_) => Vec::new(),
+                None => suggest_components(&source.to_string(), config).await,
+            };
+
+            Err(RegistryError::ComponentNotFound {
+                name: source.to_string(),
+                suggestions,
+            })
+        }
+        // The version an item was fetched at is recorded even when the item doesn't list it.
+        result => result.map(|mut item| {
+            if item.version.is_none() {
+                item.version = source.version().map(String::from);
+            }
+
+            item
+        }),
+    }
+}
+
+/// Decodes a registry document, reporting the path of the offending field when it is invalid.
+pub fn parse_registry_json<T: DeserializeOwned>(
+    data: &str,
+    location: &str,
+) -> Result<T, RegistryError> {
+    let deserializer = &mut serde_json::Deserializer::from_str(data);
+
+    serde_path_to_error::deserialize(deserializer).map_err(|e| RegistryError::InvalidItemJson {
+        location: location.to_string(),
+        path: e.path().to_string(),
+        message: e.inner().to_string(),
+    })
+}
+
+/// Looks up the names in the registry index closest to a component that wasn't found.
+async fn suggest_components(component: &str, config: &Config) -> Vec<String> {
+    let (namespace, name) = match parse_namespace(component) {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, component),
+    };
+
+    let Ok(index) = resolve_registry_index(namespace, config).await else {
+        return Vec::new();
+    };
+
+    let max_distance = (name.len() / 3).max(2);
+
+    let mut candidates = index
+        .items
+        .iter()
+        .map(|item| (strsim::damerau_levenshtein(name, &item.name), &item.name))
+        .filter(|(distance, candidate)| {
+            *distance <= max_distance
+                || candidate.contains(name)
+                || name.contains(candidate.as_str())
+        })
+        .collect::<Vec<_>>();
+
+    candidates.sort();
+
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| qualified_name(namespace, candidate))
+        .collect()
+}
+
+/// Splits a namespaced component such as `@acme/card` into its registry and name.
+pub fn parse_namespace(component: &str) -> Option<(&str, &str)> {
+    if !component.starts_with('@') {
+        return None;
+    }
+
+    component.split_once('/')
+}
+
+/// Builds the URL of a component, using the registry template configured for its namespace
+/// or the default lumina registry for unprefixed names.
+pub fn resolve_registry_url(component: &str, config: &Config) -> Result<String, RegistryError> {
+    let (template, name) = match parse_namespace(component) {
+        Some((namespace, name)) => (
+            config
+                .registries
+                .get(namespace)
+                .ok_or_else(|| RegistryError::UnknownRegistry(namespace.to_string()))?
+                .url(),
+            name,
+        ),
+        None => (DEFAULT_REGISTRY_URL, component),
+    };
+
+    if template.contains("{name}") {
+        Ok(template.replace("{name}", name))
+    } else {
+        Ok(format!("{}/{name}.json", template.trim_end_matches('/')))
+    }
+}
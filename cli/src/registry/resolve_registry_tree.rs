@@ -0,0 +1,248 @@
+use crate::config::Config;
+use crate::registry::registry_source::RegistrySource;
+use crate::registry::resolve_registry_item::{RegistryError, resolve_registry_item};
+use crate::registry::resolve_registry_version::RequestedVersion;
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::step::spinner;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use indicatif::MultiProgress;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Visiting,
+    Done,
+}
+
+#[derive(Debug)]
+pub struct ResolvedRegistryItem {
+    pub source: RegistrySource,
+    pub item: RegistryItem,
+}
+
+/// Resolved items keyed by their unversioned source, along with the unversioned sources of their
+/// dependencies.
+type RegistryGraph = HashMap<RegistrySource, (ResolvedRegistryItem, Vec<RegistrySource>)>;
+
+/// How many registry items are fetched at the same time unless configured otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Fetches the requested components along with every item reachable through their
+/// `registry_dependencies`, returning them de-duplicated and in install order
+/// (dependencies before their dependents). Up to `concurrency` items are fetched at once, each
+/// shown as its own spinner, and every failure is collected before giving up.
+///
+/// An item is only fetched once however many versions it is asked for at. The version passed on
+/// the command line wins, and every other requirement must be satisfied by the version picked.
+pub async fn resolve_registry_tree(
+    sources: &[RegistrySource],
+    config: &Config,
+    mp: &MultiProgress,
+    concurrency: usize,
+) -> Result<Vec<ResolvedRegistryItem>, RegistryError> {
+    let roots = requested_roots(sources)?;
+    let mut items = RegistryGraph::new();
+    let mut queue = roots.iter().cloned().collect::<VecDeque<_>>();
+    // Every source each item was asked for as, keyed by the unversioned source.
+    let mut requirements = HashMap::<RegistrySource, Vec<RegistrySource>>::new();
+
+    for root in &roots {
+        requirements.entry(root.unversioned()).or_default().push(root.clone());
+    }
+    let mut in_flight = FuturesUnordered::new();
+    let mut failures = Vec::new();
+
+    loop {
+        while in_flight.len() < concurrency.max(1) {
+            let Some(source) = queue.pop_front() else {
+                break;
+            };
+
+            let pb = spinner(mp, format!("Fetching {source}"))?;
+
+            in_flight.push(async move {
+                let result = resolve_registry_item(&source, config).await;
+                (source, result, pb)
+            });
+        }
+
+        let Some((source, result, pb)) = in_flight.next().await else {
+            break;
+        };
+
+        let dependencies = result.and_then(|item| {
+            let dependencies = item
+                .registry_dependencies
+                .iter()
+                .flatten()
+                .map(|dependency| RegistrySource::parse_dependency(dependency, &source))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok((item, dependencies))
+        });
+
+        match dependencies {
+            Ok((item, dependencies)) => {
+                pb.finish_and_clear();
+
+                for dependency in &dependencies {
+                    let key = dependency.unversioned();
+                    let requested = requirements.entry(key.clone()).or_default();
+                    requested.push(dependency.clone());
+
+                    if requested.len() == 1 {
+                        queue.push_back(dependency.clone());
+                    } else if dependency.version().is_some() {
+                        // A version wins over a bare name that is still waiting to be fetched.
+                        if let Some(queued) = queue.iter_mut().find(|queued| {
+                            queued.unversioned() == key && queued.version().is_none()
+                        }) {
+                            *queued = dependency.clone();
+                        }
+                    }
+                }
+
+                items.insert(
+                    source.unversioned(),
+                    (
+                        ResolvedRegistryItem { source, item },
+                        dependencies.iter().map(RegistrySource::unversioned).collect(),
+                    ),
+                );
+            }
+            Err(e) => {
+                pb.abandon_with_message(format!("Failed to fetch {source}"));
+                failures.push((source.to_string(), e));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(RegistryError::FetchFailed(failures));
+    }
+
+    check_requirements(&items, &requirements)?;
+
+    let roots = roots.iter().map(RegistrySource::unversioned).collect::<Vec<_>>();
+    let order = topological_order(&roots, &items)?;
+
+    Ok(order
+        .into_iter()
+        .filter_map(|key| items.remove(&key).map(|(resolved, _)| resolved))
+        .collect())
+}
+
+/// De-duplicates the requested components by name, keeping the one that asks for a version.
+fn requested_roots(sources: &[RegistrySource]) -> Result<Vec<RegistrySource>, RegistryError> {
+    let mut roots = Vec::<RegistrySource>::new();
+
+    for source in sources {
+        let Some(root) = roots.iter_mut().find(|root| root.unversioned() == source.unversioned())
+        else {
+            roots.push(source.clone());
+            continue;
+        };
+
+        match (root.version(), source.version()) {
+            (_, None) => {}
+            (None, Some(_)) => *root = source.clone(),
+            (Some(kept), Some(version)) if kept == version => {}
+            (Some(_), Some(_)) => {
+                return Err(RegistryError::IncompatibleVersions {
+                    component: source.unversioned().to_string(),
+                    requested: vec![root.to_string(), source.to_string()],
+                });
+            }
+        }
+    }
+
+    Ok(roots)
+}
+
+/// Checks that the version picked for every item satisfies each version it was asked for at.
+fn check_requirements(
+    items: &RegistryGraph,
+    requirements: &HashMap<RegistrySource, Vec<RegistrySource>>,
+) -> Result<(), RegistryError> {
+    for (key, requested) in requirements {
+        let Some((resolved, _)) = items.get(key) else {
+            continue;
+        };
+
+        let version = resolved.item.version.as_deref();
+        let mut satisfied = true;
+
+        for source in requested {
+            if let Some(requested_version) = source.version() {
+                satisfied &= RequestedVersion::parse(&source.to_string(), requested_version)?
+                    .matches(version);
+            }
+        }
+
+        if !satisfied {
+            let mut conflicting = requested
+                .iter()
+                .filter(|source| source.version().is_some() || *source == &resolved.source)
+                .map(|source| source.to_string())
+                .collect::<Vec<_>>();
+            conflicting.dedup();
+
+            return Err(RegistryError::IncompatibleVersions {
+                component: key.to_string(),
+                requested: conflicting,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn topological_order(
+    roots: &[RegistrySource],
+    items: &RegistryGraph,
+) -> Result<Vec<RegistrySource>, RegistryError> {
+    let mut marks = HashMap::<&RegistrySource, Mark>::new();
+    let mut path = Vec::<&RegistrySource>::new();
+    let mut order = Vec::<RegistrySource>::with_capacity(items.len());
+
+    for root in roots {
+        visit(root, items, &mut marks, &mut path, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+fn visit<'a>(
+    source: &'a RegistrySource,
+    items: &'a RegistryGraph,
+    marks: &mut HashMap<&'a RegistrySource, Mark>,
+    path: &mut Vec<&'a RegistrySource>,
+    order: &mut Vec<RegistrySource>,
+) -> Result<(), RegistryError> {
+    match marks.get(source) {
+        Some(Mark::Done) => return Ok(()),
+        Some(Mark::Visiting) => {
+            let start = path.iter().position(|s| *s == source).unwrap_or_default();
+            let cycle =
+                path[start..].iter().chain([&source]).map(|s| s.to_string()).collect::<Vec<_>>();
+            return Err(RegistryError::DependencyCycle(cycle.join(" -> ")));
+        }
+        None => {}
+    }
+
+    marks.insert(source, Mark::Visiting);
+    path.push(source);
+
+    if let Some((_, dependencies)) = items.get(source) {
+        for dependency in dependencies {
+            visit(dependency, items, marks, path, order)?;
+        }
+    }
+
+    path.pop();
+    marks.insert(source, Mark::Done);
+    order.push(source.clone());
+
+    Ok(())
+}
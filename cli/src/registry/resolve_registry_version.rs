@@ -0,0 +1,92 @@
+use crate::config::Config;
+use crate::registry::fetch_registry::fetch_registry_json;
+use crate::registry::registry_source::{RegistrySource, split_version};
+use crate::registry::resolve_registry_item::RegistryError;
+use crate::schemas::registry_item_versions::RegistryItemVersions;
+use semver::{Version, VersionReq};
+
+/// Name of the index listing the published versions of an item, next to the versioned items.
+pub const VERSIONS_INDEX: &str = "versions";
+
+/// The version requested after the `@` of a component, e.g. `button@2.1.0` or `button@^2`.
+pub enum RequestedVersion {
+    Exact(Version),
+    Latest,
+    Range(VersionReq),
+}
+
+impl RequestedVersion {
+    pub fn parse(component: &str, version: &str) -> Result<Self, RegistryError> {
+        if version == "latest" {
+            return Ok(Self::Latest);
+        }
+
+        if let Ok(version) = Version::parse(version) {
+            return Ok(Self::Exact(version));
+        }
+
+        VersionReq::parse(version)
+            .map(Self::Range)
+            .map_err(|e| RegistryError::InvalidVersion(component.to_string(), e.to_string()))
+    }
+
+    /// Whether an item at `version` satisfies the request. `latest` accepts any version, since
+    /// the version asked for explicitly wins over it.
+    pub fn matches(&self, version: Option<&str>) -> bool {
+        let version = version.and_then(|version| Version::parse(version).ok());
+
+        match (self, version) {
+            (Self::Latest, _) => true,
+            (Self::Exact(exact), Some(version)) => *exact == version,
+            (Self::Range(range), Some(version)) => range.matches(&version),
+            (_, None) => false,
+        }
+    }
+}
+
+/// Pins a component requested at `latest` or a semver range to the newest published version
+/// that satisfies it, read from the item's versions index. Other sources are returned as is.
+pub async fn resolve_registry_version(
+    source: &RegistrySource,
+    config: &Config,
+) -> Result<RegistrySource, RegistryError> {
+    let RegistrySource::Name(component) = source else {
+        return Ok(source.clone());
+    };
+
+    let (name, Some(version)) = split_version(component) else {
+        return Ok(source.clone());
+    };
+
+    let range = match RequestedVersion::parse(component, version)? {
+        RequestedVersion::Exact(_) => return Ok(source.clone()),
+        RequestedVersion::Latest => None,
+        RequestedVersion::Range(range) => Some(range),
+    };
+
+    let index = match fetch_registry_json::<RegistryItemVersions>(&source.location(config)?, config)
+        .await
+    {
+        Err(RegistryError::NotFound(_)) => {
+            return Err(RegistryError::Unversioned(name.to_string()));
+        }
+        result => result?,
+    };
+
+    let Some(range) = range else {
+        return Ok(source.pinned(&index.latest));
+    };
+
+    let picked = index
+        .versions
+        .iter()
+        .filter_map(|version| Version::parse(version).ok())
+        .filter(|version| range.matches(version))
+        .max()
+        .ok_or_else(|| RegistryError::NoMatchingVersion {
+            component: component.clone(),
+            available: index.versions.clone(),
+        })?;
+
+    Ok(source.pinned(&picked.to_string()))
+}
@@ -1,4 +1,5 @@
 pub mod registry;
 pub mod registry_item;
 pub mod registry_item_file;
+pub mod registry_item_versions;
 pub mod registry_type;
@@ -11,9 +11,15 @@ pub struct RegistryItem {
     pub item_type: RegistryType,
     pub description: String,
     pub title: String,
+    /// Semver version the item is published at, `lumina build` keeps every version it was built
+    /// at so components can be added at an exact version or range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub dependencies: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dev_dependencies: Option<Vec<String>>,
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// The versions published for an item, written by `lumina build` to `<name>/versions.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistryItemVersions {
+    pub name: String,
+    pub latest: String,
+    /// Every published version, oldest first.
+    pub versions: Vec<String>,
+}
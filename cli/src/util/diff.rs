@@ -0,0 +1,34 @@
+use console::style;
+use similar::{ChangeTag, TextDiff};
+
+/// Renders a colored unified diff between two versions of a file, returning `None` when
+/// the contents are identical.
+pub fn unified_diff(old: &str, new: &str, old_header: &str, new_header: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut output = format!(
+        "{}\n{}\n",
+        style(format!("--- {old_header}")).red().bold(),
+        style(format!("+++ {new_header}")).green().bold()
+    );
+
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        output.push_str(&format!("{}\n", style(hunk.header()).cyan()));
+
+        for change in hunk.iter_changes() {
+            let line = format!("{}{}", change.tag(), change.value());
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            match change.tag() {
+                ChangeTag::Delete => output.push_str(&format!("{}\n", style(line).red())),
+                ChangeTag::Insert => output.push_str(&format!("{}\n", style(line).green())),
+                ChangeTag::Equal => output.push_str(&format!("{line}\n")),
+            }
+        }
+    }
+
+    Some(output)
+}
@@ -0,0 +1,24 @@
+use cfg_if::cfg_if;
+use std::{env, path::PathBuf};
+
+/// Returns the directory lumina keeps its cache in, `LUMINA_CACHE_DIR` when set and otherwise
+/// the platform's user cache directory.
+pub fn get_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("LUMINA_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    cfg_if! {
+        if #[cfg(windows)] {
+            env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("lumina").join("cache"))
+        } else if #[cfg(target_os = "macos")] {
+            env::var_os("HOME").map(|dir| PathBuf::from(dir).join("Library/Caches/lumina"))
+        } else {
+            env::var_os("XDG_CACHE_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".cache")))
+                .map(|dir| dir.join("lumina"))
+        }
+    }
+}
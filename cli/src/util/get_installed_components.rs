@@ -0,0 +1,86 @@
+use crate::config::Config;
+use crate::lockfile::{Lockfile, LockfileError, project_root};
+use crate::registry::registry_source::split_version;
+use crate::registry::resolve_registry_index::{qualified_name, resolve_registry_indexes};
+use crate::schemas::registry_item::RegistryItem;
+use crate::util::resolve_file_target_path::resolve_item_file_path;
+use std::path::PathBuf;
+
+/// A registry item that has at least one of its files present in the project.
+#[derive(Debug, Clone)]
+pub struct InstalledComponent {
+    /// The name used to add the item, e.g. `button` or `@acme/card`.
+    pub name: String,
+    /// What to pass to `lumina add` to fetch the item again.
+    pub source: String,
+    pub registry_dependencies: Vec<String>,
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+    /// Files of the item that exist in the project.
+    pub files: Vec<PathBuf>,
+}
+
+impl InstalledComponent {
+    /// Whether this component lists `name` in its `registry_dependencies`, at any version.
+    pub fn depends_on(&self, name: &str) -> bool {
+        self.registry_dependencies
+            .iter()
+            .any(|dependency| split_version(dependency).0 == name)
+    }
+}
+
+/// Finds the installed components, preferring the project's lockfile. Without one every item
+/// of the configured registries' indexes is checked against the project, skipping registries
+/// whose index can't be fetched.
+pub async fn get_installed_components(
+    config: &Config,
+) -> Result<Vec<InstalledComponent>, LockfileError> {
+    if let Some(lockfile) = Lockfile::read(&project_root(config))? {
+        return Ok(lockfile
+            .components
+            .into_iter()
+            .map(|(name, locked)| InstalledComponent {
+                files: locked
+                    .file_paths(config)
+                    .into_iter()
+                    .filter(|path| path.is_file())
+                    .collect(),
+                name,
+                source: locked.source,
+                registry_dependencies: locked.registry_dependencies,
+                dependencies: locked.dependencies,
+                dev_dependencies: locked.dev_dependencies,
+            })
+            .collect());
+    }
+
+    let mut installed = Vec::new();
+
+    for (namespace, index) in resolve_registry_indexes(config).await {
+        installed.extend(index.items.iter().filter_map(|item| {
+            let component =
+                installed_component(qualified_name(namespace, &item.name), item, config);
+            (!component.files.is_empty()).then_some(component)
+        }));
+    }
+
+    Ok(installed)
+}
+
+fn installed_component(name: String, item: &RegistryItem, config: &Config) -> InstalledComponent {
+    let files = item
+        .files
+        .iter()
+        .filter_map(|file| resolve_item_file_path(item, file, config).ok())
+        .filter(|path| path.is_file())
+        .collect();
+
+    InstalledComponent {
+        source: name.clone(),
+        name,
+        registry_dependencies: item.registry_dependencies.clone().unwrap_or_default(),
+        dependencies: item.dependencies.clone().unwrap_or_default(),
+        dev_dependencies: item.dev_dependencies.clone().unwrap_or_default(),
+        files,
+    }
+}
@@ -38,7 +38,7 @@ pub struct PackageJson {
 
     // Dependencies
     pub dependencies: Option<BTreeMap<String, String>>,
-    #[serde(rename = "dev_dependencies")]
+    #[serde(rename = "devDependencies")]
     pub dev_dependencies: Option<BTreeMap<String, String>>,
     #[serde(rename = "peerDependencies")]
     pub peer_dependencies: Option<BTreeMap<String, String>>,
@@ -1,4 +1,5 @@
 use crate::util::get_package_info::get_package_info;
+use cfg_if::cfg_if;
 use std::env::{self, current_dir};
 
 pub enum PackageRunners {
@@ -33,6 +34,47 @@ impl PackageRunners {
     }
 }
 
+impl PackageManagerKind {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Yarn => "yarn",
+            Self::Pnpm => "pnpm",
+            Self::Bun => "bun",
+        }
+    }
+
+    /// Name of the binary to spawn, windows ships the node based managers as `.cmd` shims.
+    pub const fn executable(&self) -> &'static str {
+        cfg_if! {
+            if #[cfg(windows)] {
+                match self {
+                    Self::Npm => "npm.cmd",
+                    Self::Yarn => "yarn.cmd",
+                    Self::Pnpm => "pnpm.cmd",
+                    Self::Bun => "bun",
+                }
+            } else {
+                self.as_str()
+            }
+        }
+    }
+
+    pub const fn install_subcommand(&self) -> &'static str {
+        match self {
+            Self::Npm => "install",
+            Self::Yarn | Self::Pnpm | Self::Bun => "add",
+        }
+    }
+
+    pub const fn uninstall_subcommand(&self) -> &'static str {
+        match self {
+            Self::Npm => "uninstall",
+            Self::Yarn | Self::Pnpm | Self::Bun => "remove",
+        }
+    }
+}
+
 // TODO: Eventually detect from lock file & possibly PATH later on.
 pub fn get_package_manager() -> Option<PackageManager> {
     detect_from_user_agent().or_else(detect_from_package_json)
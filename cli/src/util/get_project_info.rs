@@ -1,3 +1,4 @@
+use crate::util::transaction::Transaction;
 use log::error;
 use serde::Deserialize;
 use serde_json::{Value, json};
@@ -99,7 +100,7 @@ pub fn trim_suffix(alias: &str) -> String {
     alias.strip_suffix("/*").unwrap_or(alias).to_string()
 }
 
-pub fn write_paths(cwd: &Path) -> Result<(), TsAliasError> {
+pub fn write_paths(tx: &mut Transaction, cwd: &Path) -> Result<(), TsAliasError> {
     let tsconfig_path = cwd.join("tsconfig.json");
     let mut raw = fs::read_to_string(&tsconfig_path)
         .map_err(|e| TsAliasError::Io(tsconfig_path.clone(), e))?;
@@ -120,7 +121,7 @@ pub fn write_paths(cwd: &Path) -> Result<(), TsAliasError> {
         co.insert("baseUrl".to_string(), json!("."));
     }
 
-    fs::write(
+    tx.write(
         &tsconfig_path,
         serde_json::to_string_pretty(&root)
             .map_err(|e| TsAliasError::Json(tsconfig_path.clone(), e))?,
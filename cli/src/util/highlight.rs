@@ -0,0 +1,50 @@
+use console::colors_enabled;
+use std::{path::Path, sync::LazyLock};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::{LinesWithEndings, as_24_bit_terminal_escaped};
+
+const THEME: &str = "base16-ocean.dark";
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// Colors `content` for the terminal based on the extension of `path`, returning it untouched
+/// when colors are disabled or the language isn't known.
+pub fn highlight(content: &str, path: &Path) -> String {
+    if !colors_enabled() {
+        return content.to_string();
+    }
+
+    let (Some(syntax), Some(theme)) = (find_syntax(path), THEMES.themes.get(THEME)) else {
+        return content.to_string();
+    };
+
+    highlight_with(content, syntax, theme).unwrap_or_else(|| content.to_string())
+}
+
+fn highlight_with(content: &str, syntax: &SyntaxReference, theme: &Theme) -> Option<String> {
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut highlighted = String::with_capacity(content.len());
+
+    for line in LinesWithEndings::from(content) {
+        let ranges = highlighter.highlight_line(line, &SYNTAXES).ok()?;
+        highlighted.push_str(&as_24_bit_terminal_escaped(&ranges, false));
+    }
+
+    highlighted.push_str("\x1b[0m");
+
+    Some(highlighted)
+}
+
+// The bundled syntaxes have no TypeScript or Luau, their closest relatives are used instead.
+fn find_syntax(path: &Path) -> Option<&'static SyntaxReference> {
+    let extension = match path.extension()?.to_str()? {
+        "ts" | "tsx" | "mts" | "cts" => "js",
+        "luau" => "lua",
+        extension => extension,
+    };
+
+    SYNTAXES.find_syntax_by_extension(extension)
+}
@@ -0,0 +1,162 @@
+use crate::util::get_package_info::get_package_info;
+use crate::util::get_package_manager::{PackageManagerKind, get_package_manager};
+use std::{collections::BTreeSet, path::Path, process::Command};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InstallError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to install packages using {0}: {1}")]
+    InstallFailed(&'static str, String),
+    #[error("Failed to uninstall packages using {0}: {1}")]
+    UninstallFailed(&'static str, String),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackagesToInstall {
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+}
+
+impl PackagesToInstall {
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty() && self.dev_dependencies.is_empty()
+    }
+}
+
+/// Strips the version specifier from a package spec, e.g. `@rbxts/react@^17` -> `@rbxts/react`.
+pub fn package_name(spec: &str) -> &str {
+    let search_from = usize::from(spec.starts_with('@'));
+
+    spec[search_from..]
+        .find('@')
+        .map(|index| &spec[..index + search_from])
+        .unwrap_or(spec)
+}
+
+/// Filters out every package that is already declared in the package.json at `cwd`,
+/// as well as any duplicates between the given lists.
+pub fn missing_packages(
+    cwd: &Path,
+    dependencies: &[String],
+    dev_dependencies: &[String],
+) -> PackagesToInstall {
+    let package_json = get_package_info(cwd).unwrap_or_default();
+
+    let mut declared = [
+        &package_json.dependencies,
+        &package_json.dev_dependencies,
+        &package_json.peer_dependencies,
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|map| map.keys().cloned())
+    .collect::<BTreeSet<_>>();
+
+    let mut filter = |specs: &[String]| {
+        specs
+            .iter()
+            .filter(|spec| declared.insert(package_name(spec).to_string()))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let dependencies = filter(dependencies);
+    let dev_dependencies = filter(dev_dependencies);
+
+    PackagesToInstall {
+        dependencies,
+        dev_dependencies,
+    }
+}
+
+/// Returns the names of the given packages that are declared in the package.json at `cwd`.
+pub fn declared_packages<'a>(
+    cwd: &Path,
+    packages: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let package_json = get_package_info(cwd).unwrap_or_default();
+
+    let declared = [&package_json.dependencies, &package_json.dev_dependencies]
+        .into_iter()
+        .flatten()
+        .flat_map(|map| map.keys().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+
+    packages
+        .into_iter()
+        .map(package_name)
+        .filter(|name| declared.contains(name))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Installs the packages in at most two batches (regular and dev) using the project's
+/// detected package manager, falling back to npm.
+pub fn install_packages(cwd: &Path, packages: &PackagesToInstall) -> Result<(), InstallError> {
+    let kind = detected_package_manager();
+
+    if !packages.dependencies.is_empty() {
+        run_install(cwd, kind, &packages.dependencies, false)?;
+    }
+
+    if !packages.dev_dependencies.is_empty() {
+        run_install(cwd, kind, &packages.dev_dependencies, true)?;
+    }
+
+    Ok(())
+}
+
+/// Uninstalls the packages in a single batch using the project's detected package manager.
+pub fn uninstall_packages(cwd: &Path, packages: &[String]) -> Result<(), InstallError> {
+    let kind = detected_package_manager();
+
+    let exit_status = Command::new(kind.executable())
+        .current_dir(cwd)
+        .arg(kind.uninstall_subcommand())
+        .args(packages)
+        .status()?;
+
+    if !exit_status.success() {
+        return Err(InstallError::UninstallFailed(
+            kind.as_str(),
+            packages.join(" "),
+        ));
+    }
+
+    Ok(())
+}
+
+fn detected_package_manager() -> PackageManagerKind {
+    get_package_manager()
+        .map(|package_manager| package_manager.kind)
+        .unwrap_or(PackageManagerKind::Npm)
+}
+
+fn run_install(
+    cwd: &Path,
+    kind: PackageManagerKind,
+    packages: &[String],
+    dev: bool,
+) -> Result<(), InstallError> {
+    let mut command = Command::new(kind.executable());
+    command.current_dir(cwd).arg(kind.install_subcommand());
+
+    if dev {
+        command.arg("-D");
+    }
+
+    let exit_status = command.args(packages).status()?;
+
+    if !exit_status.success() {
+        return Err(InstallError::InstallFailed(
+            kind.as_str(),
+            packages.join(" "),
+        ));
+    }
+
+    Ok(())
+}
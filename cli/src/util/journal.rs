@@ -0,0 +1,218 @@
+use crate::lockfile::{hash_content, lock_path};
+use crate::registry::registry_cache::unix_now;
+use crate::util::resolve_file_target_path::normalize_path;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{self, Path, PathBuf};
+
+/// Every operation that changed the project, one json entry per line, oldest first.
+pub const JOURNAL_FILE: &str = ".lumina/journal";
+/// Contents replaced by an operation, stored under their hash so they can be brought back.
+pub const OBJECTS_DIR: &str = ".lumina/objects";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: u64,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    /// The arguments the command was run with.
+    pub command: String,
+    /// The entry this one reverted, set by `lumina undo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<u64>,
+    pub files: Vec<JournalFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalFile {
+    /// Relative to the project, with `/` separators.
+    pub path: String,
+    /// Hash of the content before the operation, `None` when the file was created.
+    pub before: Option<String>,
+    /// Hash of the content the operation left, `None` when the file was removed.
+    pub after: Option<String>,
+}
+
+impl JournalFile {
+    pub fn target(&self, root: &Path) -> PathBuf {
+        root.join(&self.path)
+    }
+}
+
+/// The directory the journal of a command run with `--cwd cwd` lives in, the same for every
+/// command so `undo` and `history` see what the others recorded.
+pub fn journal_root(cwd: &Path) -> io::Result<PathBuf> {
+    let absolute = path::absolute(cwd)?;
+
+    Ok(normalize_path(&absolute).unwrap_or(absolute))
+}
+
+pub fn journal_path(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => lock_path(relative),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+pub fn read_journal(root: &Path) -> io::Result<Vec<JournalEntry>> {
+    let data = match fs::read_to_string(root.join(JOURNAL_FILE)) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    data.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(io::Error::from))
+        .collect()
+}
+
+/// Appends an entry for `files`, numbered after the last one in the journal.
+pub fn append_entry(
+    root: &Path,
+    command: String,
+    undoes: Option<u64>,
+    files: Vec<JournalFile>,
+) -> io::Result<JournalEntry> {
+    let entry = JournalEntry {
+        id: read_journal(root)?.last().map_or(1, |last| last.id + 1),
+        timestamp: unix_now(),
+        command,
+        undoes,
+        files,
+    };
+
+    let path = root.join(JOURNAL_FILE);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())?;
+
+    Ok(entry)
+}
+
+/// Stores `content` under its hash, returning the hash.
+pub fn write_object(root: &Path, content: &[u8]) -> io::Result<String> {
+    let hash = hash_content(content);
+    let path = root.join(OBJECTS_DIR).join(&hash);
+
+    if !path.is_file() {
+        fs::create_dir_all(root.join(OBJECTS_DIR))?;
+        fs::write(path, content)?;
+    }
+
+    Ok(hash)
+}
+
+pub fn read_object(root: &Path, hash: &str) -> io::Result<Vec<u8>> {
+    fs::read(root.join(OBJECTS_DIR).join(hash))
+}
+
+/// The entries that are currently undone. Undoing an undo brings the entry it reverted back, so
+/// every undo flips the state of the whole chain of undos below it.
+pub fn undone_entries(entries: &[JournalEntry]) -> HashSet<u64> {
+    let reverts = entries
+        .iter()
+        .filter_map(|entry| entry.undoes.map(|undoes| (entry.id, undoes)))
+        .collect::<HashMap<_, _>>();
+    let mut undone = HashSet::new();
+
+    for entry in entries {
+        let Some(mut id) = entry.undoes else {
+            continue;
+        };
+
+        undone.insert(id);
+
+        // An undo that is undone itself brings the entry it reverted back, one that is in effect
+        // keeps it undone.
+        while let Some(&reverted) = reverts.get(&id) {
+            if undone.contains(&id) {
+                undone.remove(&reverted);
+            } else {
+                undone.insert(reverted);
+            }
+
+            id = reverted;
+        }
+    }
+
+    undone
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, undoes: Option<u64>) -> JournalEntry {
+        JournalEntry {
+            id,
+            timestamp: 0,
+            command: String::new(),
+            undoes,
+            files: Vec::new(),
+        }
+    }
+
+    fn undone(entries: &[JournalEntry]) -> Vec<u64> {
+        let mut undone = undone_entries(entries).into_iter().collect::<Vec<_>>();
+        undone.sort();
+        undone
+    }
+
+    #[test]
+    fn nothing_is_undone_without_undos() {
+        assert!(undone(&[entry(1, None), entry(2, None)]).is_empty());
+    }
+
+    #[test]
+    fn undo_marks_the_entry_undone() {
+        assert_eq!(undone(&[entry(1, None), entry(2, Some(1))]), [1]);
+    }
+
+    #[test]
+    fn undoing_an_undo_redoes_the_entry() {
+        assert_eq!(
+            undone(&[entry(1, None), entry(2, Some(1)), entry(3, Some(2))]),
+            [2]
+        );
+    }
+
+    #[test]
+    fn every_level_of_a_chain_flips() {
+        let entries = [
+            entry(3, None),
+            entry(4, Some(3)),
+            entry(5, Some(4)),
+            entry(6, Some(5)),
+        ];
+
+        assert_eq!(undone(&entries), [3, 5]);
+    }
+
+    #[test]
+    fn chains_of_different_entries_are_independent() {
+        let entries = [
+            entry(1, None),
+            entry(2, None),
+            entry(3, Some(1)),
+            entry(4, Some(2)),
+            entry(5, Some(4)),
+        ];
+
+        assert_eq!(undone(&entries), [1, 4]);
+    }
+}
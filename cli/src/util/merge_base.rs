@@ -0,0 +1,71 @@
+use crate::config::Config;
+use crate::lockfile::project_root;
+use crate::util::transaction::Transaction;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+/// Directory holding a copy of the registry content each file was last installed from, used
+/// as the common ancestor when merging upstream changes into local edits.
+pub const MERGE_BASE_DIR: &str = ".lumina/base";
+
+/// Where the merge base of `path` is kept, `None` for files outside the project (such as an alias
+/// pointing at `../shared`), which have no merge base.
+pub fn merge_base_path(path: &Path, config: &Config) -> Option<PathBuf> {
+    let root = project_root(config);
+    let relative = path.strip_prefix(&root).ok()?;
+
+    relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| root.join(MERGE_BASE_DIR).join(relative))
+}
+
+pub fn read_merge_base(path: &Path, config: &Config) -> Result<Option<String>, std::io::Error> {
+    let Some(base) = merge_base_path(path, config) else {
+        return Ok(None);
+    };
+
+    match fs::read_to_string(base) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn write_merge_base(
+    tx: &mut Transaction,
+    path: &Path,
+    content: &str,
+    config: &Config,
+) -> Result<(), std::io::Error> {
+    match merge_base_path(path, config) {
+        Some(base) => tx.write(&base, content),
+        None => Ok(()),
+    }
+}
+
+pub fn remove_merge_base(tx: &mut Transaction, path: &Path, config: &Config) {
+    if let Some(base) = merge_base_path(path, config) {
+        tx.remove(&base);
+    }
+}
+
+/// Removes the directories left empty after the merge base of `path` was removed.
+pub fn prune_merge_base(path: &Path, config: &Config) {
+    let Some(base) = merge_base_path(path, config) else {
+        return;
+    };
+    let root = project_root(config).join(MERGE_BASE_DIR);
+    let mut directory = base.parent();
+
+    while let Some(current) = directory {
+        if current == root || fs::remove_dir(current).is_err() {
+            break;
+        }
+
+        directory = current.parent();
+    }
+}
@@ -1,4 +1,17 @@
+pub mod diff;
+pub mod get_cache_dir;
+pub mod get_installed_components;
 pub mod get_package_info;
 pub mod get_package_manager;
 pub mod get_project_info;
+pub mod highlight;
+pub mod install_packages;
+pub mod journal;
+pub mod merge_base;
+pub mod pick;
+pub mod plan;
+pub mod print_table;
+pub mod resolve_file_target_path;
 pub mod step;
+pub mod transaction;
+pub mod transform_imports;
@@ -0,0 +1,199 @@
+use console::{Key, Term, style, truncate_str};
+use std::io;
+
+/// How many lines of the list are shown at once.
+const MAX_LINES: usize = 15;
+
+/// An entry of the list shown by [`pick_many`].
+#[derive(Debug)]
+pub struct PickItem {
+    /// The heading the entry is listed under, entries of a group must be next to each other.
+    pub group: String,
+    pub label: String,
+    pub checked: bool,
+}
+
+enum Line {
+    Group(usize),
+    Item(usize),
+}
+
+struct Picker<'a> {
+    prompt: &'a str,
+    items: &'a [PickItem],
+    checked: Vec<bool>,
+    query: String,
+    /// Indexes of the items matching the query.
+    visible: Vec<usize>,
+    /// Position of the highlighted item in `visible`.
+    cursor: usize,
+    /// First line of the list that is shown.
+    offset: usize,
+}
+
+/// Lets the user check any number of `items` from a list grouped under headings. Typing filters
+/// the list through `matches`, which is given an item index and the lowercased query terms.
+/// Returns the indexes of the checked items.
+pub fn pick_many(
+    prompt: &str,
+    items: &[PickItem],
+    matches: impl Fn(usize, &[String]) -> bool,
+) -> io::Result<Vec<usize>> {
+    let term = Term::stderr();
+    let mut picker = Picker {
+        prompt,
+        items,
+        checked: items.iter().map(|item| item.checked).collect(),
+        query: String::new(),
+        visible: (0..items.len()).collect(),
+        cursor: 0,
+        offset: 0,
+    };
+
+    term.hide_cursor()?;
+    let result = picker.interact(&term, matches);
+    term.show_cursor()?;
+    result?;
+
+    term.write_line(&format!(
+        "{} {} {} selected",
+        style(prompt).bold(),
+        style("·").dim(),
+        picker.checked.iter().filter(|checked| **checked).count()
+    ))?;
+
+    Ok((0..items.len()).filter(|index| picker.checked[*index]).collect())
+}
+
+impl Picker<'_> {
+    fn interact(
+        &mut self,
+        term: &Term,
+        matches: impl Fn(usize, &[String]) -> bool,
+    ) -> io::Result<()> {
+        let mut drawn = 0;
+
+        loop {
+            term.clear_last_lines(drawn)?;
+            drawn = self.render(term)?;
+
+            match term.read_key()? {
+                Key::Enter => break,
+                Key::ArrowUp => self.cursor = self.cursor.saturating_sub(1),
+                Key::ArrowDown if self.cursor + 1 < self.visible.len() => self.cursor += 1,
+                Key::Char(' ') => {
+                    if let Some(&index) = self.visible.get(self.cursor) {
+                        self.checked[index] = !self.checked[index];
+                    }
+                }
+                Key::Char(c) if !c.is_control() => {
+                    self.query.push(c);
+                    self.filter(&matches);
+                }
+                Key::Backspace => {
+                    self.query.pop();
+                    self.filter(&matches);
+                }
+                Key::Escape => {
+                    self.query.clear();
+                    self.filter(&matches);
+                }
+                _ => {}
+            }
+        }
+
+        term.clear_last_lines(drawn)
+    }
+
+    fn filter(&mut self, matches: &impl Fn(usize, &[String]) -> bool) {
+        let terms = self.query.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>();
+
+        self.visible = (0..self.items.len())
+            .filter(|index| terms.is_empty() || matches(*index, &terms))
+            .collect();
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    /// The visible items with a heading before each group.
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = Vec::new();
+
+        for (position, &index) in self.visible.iter().enumerate() {
+            let group = &self.items[index].group;
+
+            if position == 0 || self.items[self.visible[position - 1]].group != *group {
+                lines.push(Line::Group(index));
+            }
+
+            lines.push(Line::Item(position));
+        }
+
+        lines
+    }
+
+    /// Draws the prompt and the part of the list around the cursor, returning the line count.
+    fn render(&mut self, term: &Term) -> io::Result<usize> {
+        let width = term.size().1 as usize;
+        let lines = self.lines();
+
+        term.write_line(&format!(
+            "{} {} {}",
+            style(self.prompt).bold(),
+            style("›").dim(),
+            self.query
+        ))?;
+
+        if lines.is_empty() {
+            term.write_line(&style("  Nothing matches the search.").dim().to_string())?;
+            return Ok(2);
+        }
+
+        let cursor_line = lines
+            .iter()
+            .position(|line| matches!(line, Line::Item(position) if *position == self.cursor))
+            .unwrap_or_default();
+
+        // Keep the heading of the highlighted item in view when scrolling up.
+        if cursor_line <= self.offset {
+            self.offset = cursor_line.saturating_sub(1);
+        } else if cursor_line >= self.offset + MAX_LINES {
+            self.offset = cursor_line + 1 - MAX_LINES;
+        }
+
+        let shown = lines.iter().skip(self.offset).take(MAX_LINES).collect::<Vec<_>>();
+
+        for line in &shown {
+            let text = match line {
+                Line::Group(index) => {
+                    style(&self.items[*index].group).bold().underlined().to_string()
+                }
+                Line::Item(position) => {
+                    let index = self.visible[*position];
+                    let mark = if self.checked[index] {
+                        style("[x]").green()
+                    } else {
+                        style("[ ]").dim()
+                    };
+                    let pointer = if *position == self.cursor {
+                        style(">").cyan()
+                    } else {
+                        style(" ")
+                    };
+
+                    format!("{pointer} {mark} {}", self.items[index].label)
+                }
+            };
+
+            term.write_line(&truncate_str(&text, width, "…"))?;
+        }
+
+        term.write_line(
+            &style("  type to search, ↑/↓ to move, space to toggle, enter to confirm")
+                .dim()
+                .to_string(),
+        )?;
+
+        Ok(shown.len() + 2)
+    }
+}
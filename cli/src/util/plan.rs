@@ -0,0 +1,149 @@
+use crate::config::Config;
+use crate::lockfile::lock_path;
+use console::{StyledObject, style};
+use serde::Serialize;
+use std::path::Path;
+
+/// What a command would do to a single file.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    Create,
+    Overwrite,
+    /// The file differs from the registry and the user would be asked whether to overwrite it.
+    Prompt,
+    Skip,
+    Unchanged,
+    Update,
+    Merge,
+    Conflict,
+    Delete,
+}
+
+impl FileAction {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Overwrite => "overwrite",
+            Self::Prompt => "prompt",
+            Self::Skip => "skip",
+            Self::Unchanged => "unchanged",
+            Self::Update => "update",
+            Self::Merge => "merge",
+            Self::Conflict => "conflict",
+            Self::Delete => "delete",
+        }
+    }
+
+    fn styled(&self) -> StyledObject<String> {
+        let label = style(format!("{:>9}", self.as_str()));
+
+        match self {
+            Self::Create | Self::Update => label.green().bold(),
+            Self::Overwrite | Self::Merge | Self::Prompt => label.yellow().bold(),
+            Self::Conflict | Self::Delete => label.red().bold(),
+            Self::Skip | Self::Unchanged => label.dim(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    /// Relative to the project, with `/` separators.
+    pub path: String,
+    pub action: FileAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedPackages {
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+}
+
+/// Everything a mutating command would change, produced by `--dry-run` instead of touching disk.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Plan {
+    pub command: &'static str,
+    /// The components involved, dependencies included, in the order they would be processed.
+    pub components: Vec<String>,
+    pub files: Vec<PlannedFile>,
+    pub install: PlannedPackages,
+    pub uninstall: Vec<String>,
+}
+
+impl Plan {
+    pub fn new(command: &'static str) -> Self {
+        Self {
+            command,
+            components: Vec::new(),
+            files: Vec::new(),
+            install: PlannedPackages::default(),
+            uninstall: Vec::new(),
+        }
+    }
+
+    pub fn file(
+        &mut self,
+        path: &Path,
+        action: FileAction,
+        component: Option<&str>,
+        config: &Config,
+    ) {
+        self.files.push(PlannedFile {
+            path: lock_path(config.resolved_paths.relative(path)),
+            action,
+            component: component.map(String::from),
+        });
+    }
+
+    /// Prints the plan, as pretty json on stdout when `json` is set.
+    pub fn print(&self, json: bool) -> Result<(), serde_json::Error> {
+        if json {
+            println!("{}", serde_json::to_string_pretty(self)?);
+            return Ok(());
+        }
+
+        println!(
+            "{} nothing was changed, `{}` would:",
+            style("Dry run,").bold(),
+            self.command
+        );
+
+        if self.files.is_empty() {
+            println!("{}", style("  leave every file as is").dim());
+        }
+
+        for file in &self.files {
+            let component = file
+                .component
+                .as_ref()
+                .map(|component| format!(" {}", style(format!("({component})")).dim()))
+                .unwrap_or_default();
+
+            println!("{} {}{component}", file.action.styled(), file.path);
+        }
+
+        let packages = [
+            ("install", &self.install.dependencies),
+            ("install (dev)", &self.install.dev_dependencies),
+            ("uninstall", &self.uninstall),
+        ];
+
+        for (label, packages) in packages {
+            if !packages.is_empty() {
+                println!(
+                    "{} {}",
+                    style(format!("{label}:")).bold(),
+                    packages.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
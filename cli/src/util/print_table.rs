@@ -0,0 +1,44 @@
+use console::style;
+
+/// Lays out `rows` in columns padded to their widest cell, returning the header line followed by
+/// a line per row.
+pub fn table_lines<const N: usize>(
+    header: [&str; N],
+    rows: &[[String; N]],
+) -> (String, Vec<String>) {
+    let widths = (0..N)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([header[column].chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        cells
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    (
+        line(&mut header.into_iter()),
+        rows.iter().map(|row| line(&mut row.iter().map(String::as_str))).collect(),
+    )
+}
+
+/// Prints `rows` as a table below a bold, underlined header.
+pub fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    let (header, lines) = table_lines(header, rows);
+
+    println!("{}", style(header).bold().underlined());
+
+    for line in lines {
+        println!("{line}");
+    }
+}
@@ -0,0 +1,199 @@
+use crate::config::Config;
+use crate::schemas::registry_item::RegistryItem;
+use crate::schemas::registry_item_file::RegistryItemFile;
+use crate::schemas::registry_type::RegistryType;
+use crate::util::transform_imports::{is_script_file, transform_imports};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FileTargetError {
+    #[error(
+        "Could not resolve the path for components, check your tsconfig paths and ensure the directories exist"
+    )]
+    CouldNotResolveTargetPath,
+    #[error("The file path `{0}` is not allowed to leave its registry item directory")]
+    InvalidFilePath(String),
+    #[error("The item name `{0}` is not allowed to leave the directory it is installed into")]
+    InvalidItemName(String),
+    #[error("The file target `{0}` uses an alias that is not configured in this project")]
+    UnresolvedTargetAlias(String),
+    #[error("The file target `{0}` points outside of the project directory")]
+    TargetOutsideProject(String),
+    #[error("Content of component file `{0}` is empty")]
+    ContentEmpty(String),
+}
+
+/// A registry item file as it would be written into the project.
+#[derive(Debug, Clone)]
+pub struct ResolvedItemFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Resolves where every file of a registry item goes and what it will contain once the registry
+/// aliases have been rewritten for this project.
+pub fn resolve_item_files(
+    item: &RegistryItem,
+    config: &Config,
+) -> Result<Vec<ResolvedItemFile>, FileTargetError> {
+    item.files
+        .iter()
+        .map(|file| {
+            let path = resolve_item_file_path(item, file, config)?;
+            let content = file
+                .content
+                .as_deref()
+                .ok_or_else(|| FileTargetError::ContentEmpty(file.path.clone()))?;
+
+            let content = if is_script_file(&path) {
+                transform_imports(content, &path, config)
+            } else {
+                content.to_string()
+            };
+
+            Ok(ResolvedItemFile { path, content })
+        })
+        .collect()
+}
+
+/// Resolves the directory the files of the given type are installed into.
+pub fn resolve_file_target_path(file: &RegistryItemFile, config: &Config) -> Option<PathBuf> {
+    match file.item_type {
+        RegistryType::Component => config.resolved_paths.components.clone(),
+        RegistryType::Block => config.resolved_paths.components.clone(),
+        RegistryType::UI => config.resolved_paths.ui.clone(),
+        _ => config.resolved_paths.components.clone(),
+    }
+}
+
+/// Resolves the full path a file of a registry item is written to. An explicit `target` wins,
+/// otherwise the file keeps its name and its subdirectories relative to the item root,
+/// e.g. `ui/button/variants/button-variants.ts`.
+pub fn resolve_item_file_path(
+    item: &RegistryItem,
+    file: &RegistryItemFile,
+    config: &Config,
+) -> Result<PathBuf, FileTargetError> {
+    if let Some(target) = &file.target {
+        return resolve_explicit_target(target, config);
+    }
+
+    let target_dir =
+        resolve_file_target_path(file, config).ok_or(FileTargetError::CouldNotResolveTargetPath)?;
+
+    // The item name comes from the registry too, so it can't leave the target directory either.
+    let name = path_components(&item.name)
+        .map_err(|_| FileTargetError::InvalidItemName(item.name.clone()))?;
+    let root = item_root(&item.files)?;
+    let components = path_components(&file.path)?;
+
+    Ok(name
+        .iter()
+        .chain(components.iter().skip(root.len()))
+        .fold(target_dir, |path, component| path.join(component)))
+}
+
+/// Resolves a `target` such as `@/ui/button/extra.tsx`, `{components}/charts/bar.tsx` or a
+/// path relative to the project root (optionally prefixed with `~/`).
+fn resolve_explicit_target(target: &str, config: &Config) -> Result<PathBuf, FileTargetError> {
+    let root = config
+        .resolved_paths
+        .cwd
+        .as_deref()
+        .ok_or(FileTargetError::CouldNotResolveTargetPath)?;
+
+    let (base, rest) = match split_target_alias(target) {
+        Some((alias, rest)) => {
+            let base = config
+                .resolved_paths
+                .get(alias)
+                .ok_or_else(|| FileTargetError::UnresolvedTargetAlias(target.to_string()))?;
+            (base.to_path_buf(), rest)
+        }
+        None if target.starts_with('@') || target.starts_with('{') => {
+            return Err(FileTargetError::UnresolvedTargetAlias(target.to_string()));
+        }
+        None => (
+            root.to_path_buf(),
+            target.strip_prefix("~/").unwrap_or(target),
+        ),
+    };
+
+    if Path::new(rest).has_root() {
+        return Err(FileTargetError::TargetOutsideProject(target.to_string()));
+    }
+
+    let path = normalize_path(&base.join(rest))
+        .filter(|path| path.starts_with(root) && path != root)
+        .ok_or_else(|| FileTargetError::TargetOutsideProject(target.to_string()))?;
+
+    Ok(path)
+}
+
+/// Splits `@/ui/button.tsx` or `{ui}/button.tsx` into the alias name and the remaining path.
+fn split_target_alias(target: &str) -> Option<(&str, &str)> {
+    let (alias, rest) = if let Some(stripped) = target.strip_prefix("@/") {
+        stripped.split_once('/').unwrap_or((stripped, ""))
+    } else {
+        let stripped = target.strip_prefix('{')?;
+        let (alias, rest) = stripped.split_once('}')?;
+        (alias, rest.trim_start_matches('/'))
+    };
+
+    Some((alias, rest))
+}
+
+/// Lexically resolves `.` and `..` components, returning `None` if the path climbs above its root.
+pub fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    Some(normalized)
+}
+
+/// The deepest directory shared by every file of a registry item.
+fn item_root(files: &[RegistryItemFile]) -> Result<Vec<&str>, FileTargetError> {
+    let mut root: Option<Vec<&str>> = None;
+
+    for file in files {
+        let components = path_components(&file.path)?;
+        let directory = &components[..components.len().saturating_sub(1)];
+
+        root = Some(match root {
+            None => directory.to_vec(),
+            Some(root) => root
+                .iter()
+                .zip(directory)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| *a)
+                .collect(),
+        });
+    }
+
+    Ok(root.unwrap_or_default())
+}
+
+fn path_components(path: &str) -> Result<Vec<&str>, FileTargetError> {
+    let components = path
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+
+    if components.is_empty() || components.contains(&"..") {
+        return Err(FileTargetError::InvalidFilePath(path.to_string()));
+    }
+
+    Ok(components)
+}
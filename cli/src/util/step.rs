@@ -1,6 +1,6 @@
 use console::{Emoji, style};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle, style::TemplateError};
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
 pub static LOOKING_GLASS: Emoji<'_, '_> = Emoji("🔍  ", "");
 pub static TRUCK: Emoji<'_, '_> = Emoji("🚚  ", "");
@@ -83,6 +83,22 @@ impl Step {
     }
 }
 
+/// Adds a spinner for a single unit of work running alongside others, e.g. one registry fetch.
+pub fn spinner<M>(multi: &MultiProgress, message: M) -> Result<ProgressBar, TemplateError>
+where
+    M: Into<Cow<'static, str>>,
+{
+    let pb = multi.add(ProgressBar::new_spinner());
+
+    pb.set_style(ProgressStyle::with_template(
+        "    {spinner} {wide_msg:.dim}",
+    )?);
+    pb.set_message(message);
+    pb.enable_steady_tick(Duration::from_millis(80));
+
+    Ok(pb)
+}
+
 #[macro_export]
 macro_rules! step {
     ($step:expr,$emoji:expr,$message:expr) => {
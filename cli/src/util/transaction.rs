@@ -0,0 +1,279 @@
+use crate::lockfile::hash_content;
+use crate::util::journal::{JOURNAL_FILE, JournalFile, append_entry, journal_path, write_object};
+use signal_hook::consts::SIGINT;
+use signal_hook::flag;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::{env, fs, io, process};
+use thiserror::Error;
+
+/// Directory inside the project that changes are staged in before being moved into place. It
+/// lives next to the files it replaces so moving them is a rename on the same file system.
+pub const TRANSACTION_DIR: &str = ".lumina/tmp";
+
+static INTERRUPTED: LazyLock<Arc<AtomicBool>> = LazyLock::new(Arc::default);
+
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+    #[error("Failed to write `{0}`, every change was rolled back: {1}")]
+    RolledBack(PathBuf, #[source] io::Error),
+    #[error("Interrupted, every change was rolled back")]
+    Interrupted,
+    #[error(
+        "Failed to write `{0}` and to roll back the changes made so far, the original files are kept in `{1}`"
+    )]
+    RollbackFailed(PathBuf, PathBuf),
+}
+
+/// Makes Ctrl-C set a flag that [`Transaction::commit`] checks instead of killing the process
+/// halfway through moving files. Pressing it a second time exits right away.
+pub fn watch_interrupts() -> io::Result<()> {
+    flag::register_conditional_shutdown(SIGINT, 130, Arc::clone(&INTERRUPTED))?;
+    flag::register(SIGINT, Arc::clone(&INTERRUPTED))?;
+
+    Ok(())
+}
+
+fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// A set of file writes and removals that are applied all at once by [`Transaction::commit`].
+/// Content is staged on disk as it comes in; nothing in the project changes until the commit,
+/// and dropping an uncommitted transaction throws the staged content away.
+#[derive(Debug)]
+pub struct Transaction {
+    root: PathBuf,
+    dir: PathBuf,
+    /// Target paths in the order they were changed, with their staged content, `None` when the
+    /// file is removed.
+    changes: Vec<(PathBuf, Option<PathBuf>)>,
+    /// Number of files staged so far, used to name the next one.
+    staged: usize,
+    /// The journal entry this transaction reverts.
+    undoes: Option<u64>,
+    /// Set when a failed rollback left original files behind in `dir`.
+    keep: bool,
+}
+
+/// A change that has been made to the project, kept to undo it.
+struct Applied {
+    target: PathBuf,
+    written: bool,
+    backup: Option<PathBuf>,
+    created_dirs: Vec<PathBuf>,
+}
+
+impl Transaction {
+    /// Starts an empty transaction, the staging directory is only created on the first write.
+    pub fn begin(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dir: root.join(TRANSACTION_DIR).join(process::id().to_string()),
+            changes: Vec::new(),
+            staged: 0,
+            undoes: None,
+            keep: false,
+        }
+    }
+
+    pub fn write(&mut self, path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+        let staged = self.dir.join("staged");
+        fs::create_dir_all(&staged)?;
+
+        let staged = staged.join(self.staged.to_string());
+        self.staged += 1;
+        fs::write(&staged, content)?;
+
+        self.change(path, Some(staged));
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.change(path, None);
+    }
+
+    fn change(&mut self, path: &Path, staged: Option<PathBuf>) {
+        self.changes.retain(|(target, _)| target != path);
+        self.changes.push((path.to_path_buf(), staged));
+    }
+
+    /// Records the commit as the undo of journal entry `id`.
+    pub fn undoing(&mut self, id: u64) {
+        self.undoes = Some(id);
+    }
+
+    /// Moves every staged change into place, backing up the files it replaces, and records it in
+    /// the journal. When anything fails, or Ctrl-C is pressed, the changes made so far are undone
+    /// from the backups.
+    pub fn commit(mut self) -> Result<(), TransactionError> {
+        let mut applied = Vec::new();
+        let mut failure = None;
+
+        for (index, (target, staged)) in self.changes.iter().enumerate() {
+            if interrupted() {
+                failure = Some((target.clone(), None));
+                break;
+            }
+
+            if let Err(error) = self.apply(index, target, staged.as_deref(), &mut applied) {
+                failure = Some((target.clone(), Some(error)));
+                break;
+            }
+        }
+
+        // An operation that can't be undone later isn't made at all.
+        if failure.is_none()
+            && let Err(error) = self.record(&applied)
+        {
+            failure = Some((self.root.join(JOURNAL_FILE), Some(error)));
+        }
+
+        let Some((target, error)) = failure else {
+            return Ok(());
+        };
+
+        if !rollback(&applied) {
+            self.keep = true;
+            return Err(TransactionError::RollbackFailed(
+                target,
+                self.dir.join("backup"),
+            ));
+        }
+
+        Err(match error {
+            Some(error) => TransactionError::RolledBack(target, error),
+            None => TransactionError::Interrupted,
+        })
+    }
+
+    /// Appends the applied changes to the journal, leaving out files that ended up unchanged.
+    fn record(&self, applied: &[Applied]) -> io::Result<()> {
+        let mut files = Vec::new();
+
+        for change in applied {
+            let before = change.backup.as_ref().map(fs::read).transpose()?;
+            let after = change.written.then(|| fs::read(&change.target)).transpose()?;
+
+            if before == after {
+                continue;
+            }
+
+            files.push(JournalFile {
+                path: journal_path(&self.root, &change.target),
+                before: before.map(|before| write_object(&self.root, &before)).transpose()?,
+                after: after.map(hash_content),
+            });
+        }
+
+        if !files.is_empty() {
+            let command = env::args().skip(1).collect::<Vec<_>>().join(" ");
+            append_entry(&self.root, command, self.undoes, files)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply(
+        &self,
+        index: usize,
+        target: &Path,
+        staged: Option<&Path>,
+        applied: &mut Vec<Applied>,
+    ) -> io::Result<()> {
+        // Backups keep their path within the project, so they can be found again by hand.
+        let backup = self
+            .dir
+            .join("backup")
+            .join(target.strip_prefix(&self.root).unwrap_or(Path::new(&index.to_string())));
+
+        if let Some(parent) = backup.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let backup = match fs::rename(target, &backup) {
+            Ok(()) => Some(backup),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        applied.push(Applied {
+            target: target.to_path_buf(),
+            written: staged.is_some(),
+            backup,
+            created_dirs: Vec::new(),
+        });
+
+        let Some(staged) = staged else {
+            return Ok(());
+        };
+
+        if let Some(parent) = target.parent() {
+            let created = missing_dirs(parent);
+            fs::create_dir_all(parent)?;
+
+            if let Some(last) = applied.last_mut() {
+                last.created_dirs = created;
+            }
+        }
+
+        fs::rename(staged, target)
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
+
+        let _ = fs::remove_dir_all(&self.dir);
+
+        // Prune the parents of the transaction directory if nothing else is in them.
+        let mut directory = self.dir.parent();
+
+        for _ in Path::new(TRANSACTION_DIR).components() {
+            match directory {
+                Some(current) if fs::remove_dir(current).is_ok() => directory = current.parent(),
+                _ => break,
+            }
+        }
+    }
+}
+
+/// Undoes applied changes newest first, returning whether everything was restored.
+fn rollback(applied: &[Applied]) -> bool {
+    let mut restored = true;
+
+    for change in applied.iter().rev() {
+        if change.written {
+            match fs::remove_file(&change.target) {
+                Err(e) if e.kind() != ErrorKind::NotFound => restored = false,
+                _ => {}
+            }
+        }
+
+        if let Some(backup) = &change.backup {
+            restored &= fs::rename(backup, &change.target).is_ok();
+        }
+
+        for dir in &change.created_dirs {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    restored
+}
+
+/// The directories `create_dir_all(path)` would create, deepest first.
+fn missing_dirs(path: &Path) -> Vec<PathBuf> {
+    path.ancestors()
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .map(Path::to_path_buf)
+        .collect()
+}
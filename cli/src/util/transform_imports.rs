@@ -0,0 +1,81 @@
+use crate::config::Config;
+use regex::{Captures, Regex};
+use std::path::{Component, Path};
+use std::sync::LazyLock;
+
+/// Aliases registry content is authored against, in the order they are matched.
+const REGISTRY_ALIASES: [&str; 5] = ["ui", "components", "utils", "hooks", "lib"];
+
+static MODULE_SPECIFIER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)(["'])(@/[^"'\n]+)(["'])"#)
+        .unwrap()
+});
+
+/// Whether the file at `path` contains module specifiers that should be rewritten.
+pub fn is_script_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "ts" | "tsx" | "js" | "jsx"))
+}
+
+/// Rewrites the registry's canonical aliases (`@/ui`, `@/components`, ...) in `content` to the
+/// aliases configured in components.json. Aliases that aren't configured fall back to a path
+/// relative to `file_path`, specifiers that can't be resolved are left untouched.
+pub fn transform_imports(content: &str, file_path: &Path, config: &Config) -> String {
+    MODULE_SPECIFIER
+        .replace_all(content, |captures: &Captures| {
+            let specifier = &captures[3];
+
+            match rewrite_specifier(specifier, file_path, config) {
+                Some(rewritten) => format!(
+                    "{}{}{rewritten}{}",
+                    &captures[1], &captures[2], &captures[4]
+                ),
+                None => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+fn rewrite_specifier(specifier: &str, file_path: &Path, config: &Config) -> Option<String> {
+    let stripped = specifier.strip_prefix("@/")?;
+
+    let (alias, rest) = REGISTRY_ALIASES.iter().find_map(|alias| {
+        let rest = stripped.strip_prefix(alias)?;
+        (rest.is_empty() || rest.starts_with('/')).then_some((*alias, rest))
+    })?;
+
+    if let Some(user_alias) = config.aliases.get(alias) {
+        return Some(format!("{}{rest}", user_alias.trim_end_matches('/')));
+    }
+
+    let target = config.resolved_paths.get(alias)?.join(rest.trim_start_matches('/'));
+    relative_specifier(file_path.parent()?, &target)
+}
+
+/// Builds a `./` or `../` specifier pointing from the directory `from` to `to`.
+fn relative_specifier(from: &Path, to: &Path) -> Option<String> {
+    let from = from.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+
+    let shared = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    if shared == 0 {
+        return None;
+    }
+
+    let mut parts = vec![".."; from.len() - shared];
+
+    if parts.is_empty() {
+        parts.push(".");
+    }
+
+    for component in &to[shared..] {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            _ => return None,
+        }
+    }
+
+    Some(parts.join("/"))
+}